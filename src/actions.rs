@@ -0,0 +1,475 @@
+// TODO https://github.com/rust-lang/rust-clippy/issues/6902
+#![allow(clippy::use_self)]
+
+use std::collections::BTreeMap;
+
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem};
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::gamepad_utils::gamepad_just_pressed;
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Bindings>();
+        app.init_resource::<Actions>();
+
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            read_actions
+                .label(ActionSystems)
+                .after(InputSystem)
+                .after(UiSystem::Focus),
+        );
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemLabel)]
+pub struct ActionSystems;
+
+/// Something the player can do, independently of the input that triggers it.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum Action {
+    /// Grabs the piece under the cursor.
+    PickUp,
+    Place,
+    Rotate,
+    /// Flips the held piece, on levels that allow it.
+    Mirror,
+    Undo,
+    Restart,
+    Pause,
+    /// Leaves menus that have somewhere to go back to.
+    Back,
+    ToggleFullscreen,
+    DebugMenu,
+    /// Grabs the piece in the given belt slot, counting from one.
+    SelectSlot(u8),
+}
+
+pub const NUM_SLOT_ACTIONS: u8 = 9;
+
+bitflags! {
+    /// When actions can fire. Actions that never fire together can share an
+    /// input, like Escape pausing the game and leaving menus.
+    struct ActionContexts: u8 {
+        const PICKING = 1;
+        const HOLDING = 1 << 1;
+        const MENUS = 1 << 2;
+        const PLAYING = Self::PICKING.bits | Self::HOLDING.bits;
+    }
+}
+
+impl Action {
+    pub fn all() -> impl Iterator<Item = Self> {
+        [
+            Self::PickUp,
+            Self::Place,
+            Self::Rotate,
+            Self::Mirror,
+            Self::Undo,
+            Self::Restart,
+            Self::Pause,
+            Self::Back,
+            Self::ToggleFullscreen,
+            Self::DebugMenu,
+        ]
+        .into_iter()
+        .chain((1..=NUM_SLOT_ACTIONS).map(Self::SelectSlot))
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::PickUp => "Pick up".to_string(),
+            Self::Place => "Place".to_string(),
+            Self::Rotate => "Rotate".to_string(),
+            Self::Mirror => "Flip".to_string(),
+            Self::Undo => "Undo".to_string(),
+            Self::Restart => "Restart level".to_string(),
+            Self::Pause => "Pause".to_string(),
+            Self::Back => "Back".to_string(),
+            Self::ToggleFullscreen => "Fullscreen".to_string(),
+            Self::DebugMenu => "Debug menu".to_string(),
+            Self::SelectSlot(slot) => format!("Belt slot {slot}"),
+        }
+    }
+
+    const fn contexts(self) -> ActionContexts {
+        match self {
+            Self::PickUp | Self::SelectSlot(_) => ActionContexts::PICKING,
+            Self::Place | Self::Rotate | Self::Mirror => ActionContexts::HOLDING,
+            Self::Undo | Self::Restart | Self::Pause => ActionContexts::PLAYING,
+            Self::Back => ActionContexts::MENUS,
+            Self::ToggleFullscreen | Self::DebugMenu => ActionContexts::all(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub const fn is_gamepad(self) -> bool {
+        matches!(self, Self::Gamepad(_))
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Key(key) => format!("{key:?}"),
+            Self::Mouse(button) => format!("Mouse {button:?}"),
+            Self::Gamepad(button) => format!("{button:?}"),
+        }
+    }
+}
+
+/// The inputs bound to each action. Actions can have any number of bindings.
+#[derive(Debug, Clone, Eq, PartialEq, Deref, DerefMut, Serialize, Deserialize)]
+pub struct Bindings(BTreeMap<Action, Vec<Binding>>);
+
+const SLOT_KEYS: [KeyCode; NUM_SLOT_ACTIONS as usize] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key, Mouse};
+
+        let mut bindings = BTreeMap::from([
+            (Action::PickUp, vec![Mouse(MouseButton::Left)]),
+            (
+                Action::Place,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Key(KeyCode::Return),
+                    Key(KeyCode::Space),
+                    Gamepad(GamepadButtonType::South),
+                ],
+            ),
+            (
+                Action::Rotate,
+                vec![
+                    Mouse(MouseButton::Right),
+                    Key(KeyCode::R),
+                    Gamepad(GamepadButtonType::East),
+                ],
+            ),
+            (
+                Action::Mirror,
+                vec![Key(KeyCode::M), Gamepad(GamepadButtonType::West)],
+            ),
+            (
+                Action::Undo,
+                vec![Key(KeyCode::Z), Gamepad(GamepadButtonType::North)],
+            ),
+            (Action::Restart, vec![Gamepad(GamepadButtonType::Select)]),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)],
+            ),
+            (
+                Action::Back,
+                vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::East)],
+            ),
+            (Action::ToggleFullscreen, vec![Key(KeyCode::F)]),
+            (Action::DebugMenu, vec![Key(KeyCode::Semicolon)]),
+        ]);
+
+        for (slot, key) in (1..=NUM_SLOT_ACTIONS).zip(SLOT_KEYS) {
+            bindings.insert(Action::SelectSlot(slot), vec![Key(key)]);
+        }
+        // Gamepads pick up the first piece with the same button that places it
+        bindings
+            .get_mut(&Action::SelectSlot(1))
+            .unwrap()
+            .push(Gamepad(GamepadButtonType::South));
+
+        Self(bindings)
+    }
+}
+
+impl Bindings {
+    /// Takes the saved bindings over the defaults, keeping the defaults of
+    /// actions added since the save was made.
+    pub fn restore(&mut self, saved: &Self) {
+        self.extend(
+            saved
+                .iter()
+                .map(|(action, bindings)| (*action, bindings.clone())),
+        );
+    }
+
+    pub fn for_action(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// The action's keyboard and mouse or gamepad bindings, in order.
+    pub fn of_kind(&self, action: Action, gamepad: bool) -> impl Iterator<Item = Binding> + '_ {
+        self.for_action(action)
+            .iter()
+            .copied()
+            .filter(move |binding| binding.is_gamepad() == gamepad)
+    }
+
+    /// Puts the binding in the given slot of the action's bindings of the
+    /// same kind, adding it if the slot is empty. Actions that can fire
+    /// alongside this one and already use the binding get the one it replaces
+    /// instead, or lose it if the slot was empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the action that would be left without any bindings, in which
+    /// case nothing changes.
+    pub fn rebind(&mut self, action: Action, slot: usize, binding: Binding) -> Result<(), Action> {
+        let replaced = self.of_kind(action, binding.is_gamepad()).nth(slot);
+        if replaced == Some(binding) {
+            return Ok(());
+        }
+
+        let conflicts = self
+            .iter()
+            .filter(|(&other, bindings)| {
+                other != action
+                    && other.contexts().intersects(action.contexts())
+                    && bindings.contains(&binding)
+            })
+            .map(|(&other, _)| other)
+            .collect::<SmallVec<[_; 2]>>();
+        if replaced.is_none()
+            && let Some(&stranded) = conflicts
+                .iter()
+                .find(|&&other| self.for_action(other).len() == 1)
+        {
+            return Err(stranded);
+        }
+
+        for other in conflicts {
+            let bindings = self.get_mut(&other).unwrap();
+            let duplicate = bindings
+                .iter()
+                .position(|&existing| existing == binding)
+                .unwrap();
+            match replaced {
+                Some(replaced) if !bindings.contains(&replaced) => bindings[duplicate] = replaced,
+                _ => {
+                    bindings.remove(duplicate);
+                }
+            }
+        }
+
+        let bindings = self.entry(action).or_default();
+        let index = bindings
+            .iter()
+            .enumerate()
+            .filter(|(_, existing)| existing.is_gamepad() == binding.is_gamepad())
+            .nth(slot)
+            .map(|(index, _)| index);
+        if let Some(index) = index {
+            bindings[index] = binding;
+        } else {
+            bindings.push(binding);
+        }
+        Ok(())
+    }
+}
+
+/// The actions triggered this frame.
+#[derive(Default)]
+pub struct Actions {
+    just_pressed: SmallVec<[Action; 2]>,
+}
+
+impl Actions {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+fn read_actions(
+    mut actions: ResMut<Actions>,
+    bindings: Res<Bindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    ui_buttons: Query<&Interaction, With<Button>>,
+) {
+    // Clicks on buttons are meant for the buttons alone
+    let over_button = ui_buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+
+    actions.just_pressed.clear();
+    for (action, bindings) in bindings.iter() {
+        let pressed = bindings.iter().any(|binding| match *binding {
+            Binding::Key(key) => keyboard_input.just_pressed(key),
+            Binding::Mouse(button) => !over_button && mouse_button_input.just_pressed(button),
+            Binding::Gamepad(button) => gamepad_just_pressed(&gamepads, &gamepad_buttons, button),
+        });
+        if pressed {
+            actions.just_pressed.push(*action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restoring_keeps_defaults_of_unsaved_actions() {
+        let mut saved = Bindings(BTreeMap::new());
+        saved.insert(Action::Rotate, vec![Binding::Key(KeyCode::Q)]);
+
+        let mut bindings = Bindings::default();
+        bindings.restore(&saved);
+
+        assert_eq!(
+            bindings.for_action(Action::Rotate),
+            [Binding::Key(KeyCode::Q)]
+        );
+        assert_eq!(
+            bindings.for_action(Action::Undo),
+            Bindings::default().for_action(Action::Undo)
+        );
+    }
+
+    #[test]
+    fn rebinding_replaces_only_the_edited_slot() {
+        let mut bindings = Bindings::default();
+
+        bindings
+            .rebind(Action::Place, 1, Binding::Key(KeyCode::P))
+            .unwrap();
+
+        assert_eq!(
+            bindings.for_action(Action::Place),
+            [
+                Binding::Mouse(MouseButton::Left),
+                Binding::Key(KeyCode::P),
+                Binding::Key(KeyCode::Space),
+                Binding::Gamepad(GamepadButtonType::South),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_past_the_last_slot_adds_a_binding() {
+        let mut bindings = Bindings::default();
+
+        bindings
+            .rebind(Action::Mirror, 1, Binding::Key(KeyCode::N))
+            .unwrap();
+
+        assert_eq!(
+            bindings.for_action(Action::Mirror),
+            [
+                Binding::Key(KeyCode::M),
+                Binding::Gamepad(GamepadButtonType::West),
+                Binding::Key(KeyCode::N),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_to_a_used_input_swaps_them() {
+        let mut bindings = Bindings::default();
+
+        bindings
+            .rebind(Action::Undo, 0, Binding::Key(KeyCode::R))
+            .unwrap();
+        assert_eq!(
+            bindings.for_action(Action::Rotate),
+            [
+                Binding::Mouse(MouseButton::Right),
+                Binding::Key(KeyCode::Z),
+                Binding::Gamepad(GamepadButtonType::East),
+            ]
+        );
+        assert_eq!(
+            bindings.of_kind(Action::Undo, false).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::R)]
+        );
+    }
+
+    #[test]
+    fn adding_a_used_input_takes_it_from_the_other_action() {
+        let mut bindings = Bindings::default();
+
+        bindings
+            .rebind(Action::Undo, 1, Binding::Key(KeyCode::M))
+            .unwrap();
+
+        assert_eq!(
+            bindings.for_action(Action::Mirror),
+            [Binding::Gamepad(GamepadButtonType::West)]
+        );
+        assert_eq!(
+            bindings.of_kind(Action::Undo, false).collect::<Vec<_>>(),
+            [Binding::Key(KeyCode::Z), Binding::Key(KeyCode::M)]
+        );
+    }
+
+    #[test]
+    fn rebinding_leaves_inputs_shared_with_actions_that_never_fire_alongside() {
+        let mut bindings = Bindings::default();
+
+        bindings
+            .rebind(Action::Rotate, 0, Binding::Mouse(MouseButton::Left))
+            .unwrap();
+        bindings
+            .rebind(Action::Undo, 1, Binding::Key(KeyCode::Escape))
+            .unwrap();
+
+        assert_eq!(
+            bindings.for_action(Action::PickUp),
+            [Binding::Mouse(MouseButton::Left)]
+        );
+        assert_eq!(
+            bindings.for_action(Action::Place)[0],
+            Binding::Mouse(MouseButton::Right)
+        );
+        assert_eq!(
+            bindings.for_action(Action::Back),
+            Bindings::default().for_action(Action::Back)
+        );
+        assert_eq!(
+            bindings.for_action(Action::Pause),
+            [Binding::Gamepad(GamepadButtonType::Start)]
+        );
+    }
+
+    #[test]
+    fn rebinding_refuses_to_take_the_last_binding() {
+        let mut bindings = Bindings::default();
+
+        assert_eq!(
+            bindings.rebind(Action::Undo, 1, Binding::Key(KeyCode::F)),
+            Err(Action::ToggleFullscreen)
+        );
+        assert_eq!(bindings, Bindings::default());
+    }
+
+    #[test]
+    fn bindings_round_trip() {
+        let bindings = Bindings::default();
+
+        let serialized = ron::to_string(&bindings).unwrap();
+
+        assert_eq!(ron::from_str::<Bindings>(&serialized).unwrap(), bindings);
+    }
+}
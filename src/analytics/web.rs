@@ -1,14 +1,11 @@
-use bevy::{math::const_vec3, prelude::*, tasks::AsyncComputeTaskPool};
-use bevy_rapier3d::plugin::RapierContext;
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    bags::{BagMarker, BagSize},
+    bags::{BagGrid, BagMarker},
     gb9000::GroceryBagger9000,
     levels::{CurrentScore, LevelFinished, LevelStarted, ScoringSystems},
-    nominos::{
-        Nomino, NominoColor, NominoMarker, PiecePickedUp, PiecePlaced, NOMINO_COLLIDER_GROUP,
-    },
+    nominos::{Nomino, NominoColor, NominoMarker, PiecePickedUp, PiecePlaced},
     robot::RobotOptions,
 };
 
@@ -82,34 +79,17 @@ fn log_piece_placed(
     mut piece_placed: EventReader<PiecePlaced>,
     current_score: Res<CurrentScore>,
     thread_pool: Res<AsyncComputeTaskPool>,
-    rapier_context: Res<RapierContext>,
-    bags: Query<(&GlobalTransform, &BagSize), With<BagMarker>>,
-    colors: Query<&NominoColor, With<NominoMarker>>,
+    bags: Query<&BagGrid, With<BagMarker>>,
     pieces: Query<&Nomino, With<NominoMarker>>,
 ) {
-    for PiecePlaced { bag, piece } in piece_placed.iter() {
-        let (bag_coords, bag_size) = bags.get(*bag).unwrap();
-
-        let width = bag_size.width();
-        let height = bag_size.height();
-        let block_origin = bag_coords.translation - bag_size.origin() + const_vec3!([0.5, 0.5, 0.]);
+    for PiecePlaced { bag, piece, .. } in piece_placed.iter() {
+        let grid = bags.get(*bag).unwrap();
 
         let mut bag_representation = String::with_capacity(40);
-        for row in 0..height {
-            for col in 0..width {
-                let mut color = None;
-                rapier_context.intersections_with_point(
-                    block_origin + Vec3::new(col as f32, row as f32, 0.),
-                    NOMINO_COLLIDER_GROUP.into(),
-                    None,
-                    |piece_id| {
-                        color = Some(colors.get(piece_id).unwrap());
-                        false
-                    },
-                );
-
-                if let Some(color) = color {
-                    bag_representation.push_str(&format!("{}", *color as u32 + 1));
+        for row in grid.rows() {
+            for cell in row {
+                if let Some(cell) = cell {
+                    bag_representation.push_str(&format!("{}", cell.color as u32 + 1));
                 } else {
                     bag_representation.push('0');
                 }
@@ -1,4 +1,4 @@
-use std::{f32::consts::PI, time::Duration};
+use std::{f32::consts::PI, marker::PhantomData, time::Duration};
 
 use bevy::{ecs::schedule::ShouldRun, math::const_vec3, prelude::*};
 use bevy_tweening::{
@@ -10,6 +10,11 @@ use bevy_tweening::{
 };
 use bitflags::bitflags;
 
+use crate::{
+    gb9000::GameState::{Paused, Settings},
+    run_criteria::{run_on_enter, run_on_exit},
+};
+
 pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
@@ -40,6 +45,25 @@ impl Plugin for AnimationPlugin {
         add_cleanup_animations!(Style);
         add_cleanup_animations!(Text);
 
+        macro_rules! add_pause_animations {
+            ($t:ty) => {
+                app.add_system(
+                    pause_animations::<$t>
+                        .with_run_criteria(run_on_enter(&[Paused, Settings]))
+                        .before(AnimationSystem::AnimationUpdate),
+                );
+                app.add_system(
+                    resume_animations::<$t>
+                        .with_run_criteria(run_on_exit(&[Paused, Settings]))
+                        .before(AnimationSystem::AnimationUpdate),
+                );
+            };
+        }
+
+        add_pause_animations!(Transform);
+        add_pause_animations!(Style);
+        add_pause_animations!(Text);
+
         app.add_system(handle_animation_despawns.after(AnimationSystem::AnimationUpdate));
     }
 }
@@ -97,6 +121,7 @@ impl<T: Copy> Lens<T> for TeleportLens<T> {
     }
 }
 
+#[must_use]
 pub fn error_shake(current: Transform, speed: &GameSpeed) -> UndoableAnimationBundle<Transform> {
     let wiggle = Quat::from_rotation_z(PI / 16.);
 
@@ -138,6 +163,7 @@ pub fn error_shake(current: Transform, speed: &GameSpeed) -> UndoableAnimationBu
     }
 }
 
+#[must_use]
 pub fn bag_enter(
     from: Transform,
     to: Transform,
@@ -193,6 +219,7 @@ pub fn bag_enter(
     ]))
 }
 
+#[must_use]
 pub fn bag_exit(from: Transform, to: Transform, speed: &GameSpeed) -> Animator<Transform> {
     Animator::new(
         Tween::new(
@@ -212,6 +239,7 @@ pub fn bag_exit(from: Transform, to: Transform, speed: &GameSpeed) -> Animator<T
     )
 }
 
+#[must_use]
 pub fn piece_placed(current: Transform, speed: &GameSpeed) -> Animator<Transform> {
     let pop = current.scale + const_vec3!([0.2, 0.2, 0.2]);
 
@@ -240,6 +268,7 @@ pub fn piece_placed(current: Transform, speed: &GameSpeed) -> Animator<Transform
     ]))
 }
 
+#[must_use]
 pub fn score_particle(
     from: GlobalTransform,
     to: GlobalTransform,
@@ -287,6 +316,7 @@ pub fn score_particle(
     (enter, exit)
 }
 
+#[must_use]
 pub fn piece_loaded(
     index: u8,
     from: Transform,
@@ -329,6 +359,7 @@ pub fn piece_loaded(
     }
 }
 
+#[must_use]
 pub fn piece_movement(
     from: Transform,
     to: Transform,
@@ -354,6 +385,7 @@ pub fn piece_movement(
     }
 }
 
+#[must_use]
 pub fn undo_selection(
     from: Transform,
     to: Transform,
@@ -389,6 +421,7 @@ pub fn undo_selection(
     }
 }
 
+#[must_use]
 pub fn mouse_tutorial_enter(target: Transform, speed: &GameSpeed) -> Animator<Transform> {
     Animator::new(Tracks::new([
         Tween::new(
@@ -415,6 +448,7 @@ pub fn mouse_tutorial_enter(target: Transform, speed: &GameSpeed) -> Animator<Tr
     ]))
 }
 
+#[must_use]
 pub fn mouse_tutorial_switch_rotation(
     from: Transform,
     to: Transform,
@@ -489,6 +523,7 @@ pub fn mouse_tutorial_switch_rotation(
     ]))
 }
 
+#[must_use]
 pub fn level_complete_menu_ui_enter(
     from: Rect<Val>,
     to: Rect<Val>,
@@ -509,6 +544,7 @@ pub fn level_complete_menu_ui_enter(
     )
 }
 
+#[must_use]
 pub fn level_complete_menu_ui_exit(
     from: Rect<Val>,
     to: Rect<Val>,
@@ -532,6 +568,28 @@ pub fn level_complete_menu_ui_exit(
     )
 }
 
+#[must_use]
+pub fn star_earned(index: u8, speed: &GameSpeed) -> Animator<Transform> {
+    Animator::new(Sequence::new([
+        Box::new(Delay::new(Duration::from_millis(
+            400 + 250 * u64::from(index),
+        ))) as DynTweenable,
+        Box::new(
+            Tween::new(
+                EaseMethod::CustomFunction(ease_out_back),
+                TweeningType::Once,
+                Duration::from_millis(300),
+                TransformScaleLens {
+                    start: Vec3::ZERO,
+                    end: Vec3::ONE,
+                },
+            )
+            .with_speed(**speed)
+            .with_completed_event(true, AnimationEvent::COMPLETED.bits()),
+        ) as DynTweenable,
+    ]))
+}
+
 fn run_if_game_speed_changed(game_speed: Res<GameSpeed>) -> ShouldRun {
     if game_speed.is_changed() {
         ShouldRun::Yes
@@ -549,6 +607,34 @@ fn change_animation_speed<T: Component>(
     }
 }
 
+/// Marks the animators paused along with the game so only those get resumed.
+#[derive(Component)]
+struct PausedWithGame<T: Component>(PhantomData<T>);
+
+fn pause_animations<T: Component>(
+    mut commands: Commands,
+    mut animators: Query<(Entity, &mut Animator<T>)>,
+) {
+    for (id, mut animator) in animators.iter_mut() {
+        if animator.state == AnimatorState::Playing {
+            animator.state = AnimatorState::Paused;
+            commands.entity(id).insert(PausedWithGame::<T>(PhantomData));
+        }
+    }
+}
+
+fn resume_animations<T: Component>(
+    mut commands: Commands,
+    mut animators: Query<(Entity, Option<&mut Animator<T>>), With<PausedWithGame<T>>>,
+) {
+    for (id, animator) in animators.iter_mut() {
+        if let Some(mut animator) = animator {
+            animator.state = AnimatorState::Playing;
+        }
+        commands.entity(id).remove::<PausedWithGame<T>>();
+    }
+}
+
 fn cleanup_animations<T: Component>(
     mut commands: Commands,
     mut completed_animations: EventReader<TweenCompleted>,
@@ -591,3 +677,46 @@ fn ease_out_back(x: f32) -> f32 {
     let x2 = x1 * x1;
     1. + C3.mul_add(x1 * x2, C1 * x2)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gb9000::{GameState::Playing, GroceryBagger9000};
+
+    #[test]
+    fn resuming_leaves_animations_that_were_already_paused() {
+        let mut app = App::new();
+        app.init_resource::<GroceryBagger9000>();
+        app.add_system(
+            pause_animations::<Transform>.with_run_criteria(run_on_enter(&[Paused, Settings])),
+        );
+        app.add_system(
+            resume_animations::<Transform>.with_run_criteria(run_on_exit(&[Paused, Settings])),
+        );
+
+        let animator = || {
+            Animator::new(Tween::new(
+                EaseFunction::QuadraticOut,
+                TweeningType::Once,
+                Duration::from_secs(1),
+                TransformScaleLens {
+                    start: Vec3::ZERO,
+                    end: Vec3::ONE,
+                },
+            ))
+        };
+        let playing = app.world.spawn().insert(animator()).id();
+        let mut paused = animator();
+        paused.state = AnimatorState::Paused;
+        let paused = app.world.spawn().insert(paused).id();
+
+        for state in [Playing, Paused, Settings, Playing] {
+            app.world.resource_mut::<GroceryBagger9000>().state = state;
+            app.update();
+        }
+
+        let state = |id| app.world.get::<Animator<Transform>>(id).unwrap().state;
+        assert_eq!(state(playing), AnimatorState::Playing);
+        assert_eq!(state(paused), AnimatorState::Paused);
+    }
+}
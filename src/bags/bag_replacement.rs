@@ -1,16 +1,19 @@
-use bevy::{math::const_vec3, prelude::*};
-use bevy_rapier3d::prelude::*;
+use bevy::prelude::*;
 use smallvec::SmallVec;
 
 use crate::{
     animations,
     animations::GameSpeed,
     bags::{
-        spawn::{BagContainerMarker, BagLidMarker, BagMarker},
-        BagSize, BagSpawner, BAG_LID_COLLIDER_GROUP, BAG_SIZE_LARGE,
+        grid::BagGrid,
+        spawn::{BagContainerMarker, BagMarker},
+        BagSize, BagSpawner,
     },
     conveyor_belt::BeltEmptyEvent,
-    nominos::{NominoColor, NominoMarker, PiecePlaced, PieceSystems, NOMINO_COLLIDER_GROUP},
+    nominos::{
+        Nomino, NominoColor, NominoMarker, PiecePlaced, PieceSystems, PieceUndoSystems,
+        PlacementUndone,
+    },
 };
 
 pub struct BagReplacementPlugin;
@@ -27,6 +30,11 @@ impl Plugin for BagReplacementPlugin {
                 .label(BagChangeDetectionSystems)
                 .after(PieceSystems),
         );
+        app.add_system(
+            undone_placement_detection
+                .label(BagChangeDetectionSystems)
+                .after(PieceUndoSystems),
+        );
         app.add_system(
             detect_filled_bags
                 .label(BagReplacementDetectionSystems)
@@ -63,13 +71,8 @@ pub struct BagReplacementSystems;
 #[derive(Deref)]
 pub struct BagFilled(Entity);
 
-pub struct BagChanged {
-    pub bag: Entity,
-    pub blocks: SmallVec<
-        [SmallVec<[Option<NominoColor>; BAG_SIZE_LARGE.width() as usize]>;
-            BAG_SIZE_LARGE.height() as usize],
-    >,
-}
+#[derive(Deref)]
+pub struct BagChanged(Entity);
 
 #[derive(Deref)]
 struct RemoveFilledBag(Entity);
@@ -83,81 +86,46 @@ pub struct Exiting;
 fn bag_change_detection(
     mut piece_placements: EventReader<PiecePlaced>,
     mut bag_changes: EventWriter<BagChanged>,
-    bags: Query<(&GlobalTransform, &BagSize), With<BagMarker>>,
-    color_wrapper: Query<&NominoColor, With<NominoMarker>>,
-    rapier_context: Res<RapierContext>,
+    mut bags: Query<(&GlobalTransform, &mut BagGrid), With<BagMarker>>,
+    pieces: Query<(&GlobalTransform, &Nomino, &NominoColor), With<NominoMarker>>,
 ) {
-    for PiecePlaced { bag, .. } in piece_placements.iter() {
-        let (bag_coords, bag_size) = bags.get(*bag).unwrap();
-
-        let width = bag_size.width() as usize;
-        let height = bag_size.height() as usize;
-        let block_origin = bag_coords.translation - bag_size.origin() + const_vec3!([0.5, 0.5, 0.]);
-
-        let mut blocks = SmallVec::with_capacity(height);
-        for row_num in 0..height {
-            let mut row = SmallVec::with_capacity(width);
-            for col in 0..width {
-                let mut color = None;
-                rapier_context.intersections_with_point(
-                    block_origin
-                        + Vec3::new(
-                            f32::from(u8::try_from(col).unwrap()),
-                            f32::from(u8::try_from(row_num).unwrap()),
-                            0.,
-                        ),
-                    NOMINO_COLLIDER_GROUP.into(),
-                    None,
-                    |color_id| {
-                        color = Some(*color_wrapper.get(color_id).unwrap());
-                        false
-                    },
-                );
-
-                row.push(color);
-            }
-            blocks.push(row);
-        }
+    for PiecePlaced { piece, bag, .. } in piece_placements.iter() {
+        let (bag_coords, mut grid) = bags.get_mut(*bag).unwrap();
+        let (piece_coords, nomino, color) = pieces.get(*piece).unwrap();
+
+        let blocks = grid.piece_blocks(bag_coords.translation, *nomino, (*piece_coords).into());
+        grid.insert(*piece, *color, &blocks);
 
-        bag_changes.send(BagChanged { bag: *bag, blocks });
+        bag_changes.send(BagChanged(*bag));
+    }
+}
+
+fn undone_placement_detection(
+    mut undone_placements: EventReader<PlacementUndone>,
+    mut bag_changes: EventWriter<BagChanged>,
+    mut bags: Query<&mut BagGrid, With<BagMarker>>,
+) {
+    for PlacementUndone { piece, bag } in undone_placements.iter() {
+        bags.get_mut(*bag).unwrap().remove(*piece);
+        bag_changes.send(BagChanged(*bag));
     }
 }
 
 fn detect_filled_bags(
     mut bag_changes: EventReader<BagChanged>,
-    mut piece_placements: EventReader<PiecePlaced>,
     mut filled_events: EventWriter<BagFilled>,
-    rapier_context: Res<RapierContext>,
-    piece_colliders: Query<(&GlobalTransform, &Collider), With<NominoMarker>>,
-    lid_collider_bag: Query<&Parent, With<BagLidMarker>>,
+    bags: Query<&BagGrid, With<BagMarker>>,
 ) {
     let mut processed_bags = SmallVec::<[Entity; 1]>::new();
 
-    for BagChanged { bag, blocks } in bag_changes.iter() {
-        let top_row_full = blocks.iter().last().unwrap().iter().all(Option::is_some);
-        if top_row_full {
-            filled_events.send(BagFilled(*bag));
-            processed_bags.push(*bag);
-        }
-    }
-
-    for PiecePlaced { piece, bag } in piece_placements.iter() {
+    for bag in bag_changes.iter() {
         if processed_bags.contains(bag) {
             continue;
         }
 
-        let (transform, collider) = piece_colliders.get(*piece).unwrap();
-        let bag_overflowing = rapier_context
-            .intersection_with_shape(
-                transform.translation,
-                transform.rotation,
-                collider,
-                BAG_LID_COLLIDER_GROUP.into(),
-                Some(&|entity| **lid_collider_bag.get(entity).unwrap() == *bag),
-            )
-            .is_some();
-        if bag_overflowing {
-            filled_events.send(BagFilled(*bag));
+        if bags.get(**bag).unwrap().is_filled() {
+            filled_events.send(BagFilled(**bag));
+            processed_bags.push(**bag);
         }
     }
 }
@@ -0,0 +1,240 @@
+use bevy::{math::const_vec3, prelude::*};
+use smallvec::SmallVec;
+
+use crate::{
+    bags::{BagSize, BAG_SIZE_LARGE},
+    nominos::{Nomino, NominoColor, NominoGeometry},
+};
+
+const LARGE_BAG_CAPACITY: usize = BAG_SIZE_LARGE.capacity() as usize;
+
+pub type BlockPositions = SmallVec<[IVec2; 4]>;
+
+pub type BagColors = SmallVec<
+    [SmallVec<[Option<NominoColor>; BAG_SIZE_LARGE.width() as usize]>;
+        BAG_SIZE_LARGE.height() as usize],
+>;
+
+/// The contents of a bag, laid out in rows starting from the bottom left
+/// block.
+#[derive(Debug, Clone, Component)]
+pub struct BagGrid {
+    size: BagSize,
+    cells: SmallVec<[Option<BagCell>; LARGE_BAG_CAPACITY]>,
+    overflowing: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BagCell {
+    pub color: NominoColor,
+    pub piece: Entity,
+}
+
+impl BagGrid {
+    #[must_use]
+    pub fn new(size: BagSize) -> Self {
+        Self {
+            size,
+            cells: SmallVec::from_elem(None, size.capacity().into()),
+            overflowing: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> BagSize {
+        self.size
+    }
+
+    /// Computes the blocks covered by a piece in this bag's coordinates.
+    /// Blocks may lie outside the bag.
+    #[must_use]
+    pub fn piece_blocks(
+        &self,
+        bag_position: Vec3,
+        nomino: Nomino,
+        piece: Transform,
+    ) -> BlockPositions {
+        let pivot = (piece.translation - bag_position + self.size.origin()
+            - const_vec3!([0.5, 0.5, 0.]))
+        .truncate()
+        .round()
+        .as_ivec2();
+
+        nomino
+            .blocks(piece.rotation)
+            .into_iter()
+            .map(|block| pivot + block)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn get(&self, block: IVec2) -> Option<BagCell> {
+        self.index(block).and_then(|i| self.cells[i])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Option<BagCell>]> {
+        self.cells.chunks(self.size.width().into())
+    }
+
+    #[must_use]
+    pub fn colors(&self) -> BagColors {
+        self.rows()
+            .map(|row| row.iter().map(|cell| cell.map(|c| c.color)).collect())
+            .collect()
+    }
+
+    /// Whether any block lies inside the bag.
+    #[must_use]
+    pub fn intersects(&self, blocks: &[IVec2]) -> bool {
+        blocks.iter().any(|block| self.index(*block).is_some())
+    }
+
+    /// Whether the piece would poke through the bag's walls or floor.
+    #[must_use]
+    pub fn straddles(&self, blocks: &[IVec2]) -> bool {
+        let width = i32::from(self.size.width());
+        let height = i32::from(self.size.height());
+
+        blocks.iter().any(|block| {
+            let crosses_wall = (block.x == 0 || block.x == width)
+                && (0..height).contains(&block.y)
+                && blocks.contains(&(*block - IVec2::X));
+            let crosses_floor = block.y == 0
+                && (0..width).contains(&block.x)
+                && blocks.contains(&(*block - IVec2::Y));
+
+            crosses_wall || crosses_floor
+        })
+    }
+
+    #[must_use]
+    pub fn overlaps(&self, blocks: &[IVec2]) -> bool {
+        blocks.iter().any(|block| self.get(*block).is_some())
+    }
+
+    /// Whether the piece would rest on the floor or another piece.
+    #[must_use]
+    pub fn supports(&self, blocks: &[IVec2]) -> bool {
+        blocks.iter().any(|block| {
+            (block.y == 0 && (0..i32::from(self.size.width())).contains(&block.x))
+                || self.get(*block - IVec2::Y).is_some()
+        })
+    }
+
+    /// Fills the blocks covered by the piece. Blocks sticking out of the top
+    /// of the bag overflow it.
+    pub fn insert(&mut self, piece: Entity, color: NominoColor, blocks: &[IVec2]) {
+        for block in blocks {
+            if let Some(i) = self.index(*block) {
+                self.cells[i] = Some(BagCell { color, piece });
+            } else if block.y >= i32::from(self.size.height()) {
+                self.overflowing = true;
+            }
+        }
+    }
+
+    /// Empties the cells covered by the piece.
+    pub fn remove(&mut self, piece: Entity) {
+        for cell in &mut self.cells {
+            if cell.map_or(false, |c| c.piece == piece) {
+                *cell = None;
+            }
+        }
+    }
+
+    /// A bag is filled once its top row is full or a piece sticks out of it.
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        self.overflowing
+            || self
+                .rows()
+                .last()
+                .map_or(false, |row| row.iter().all(Option::is_some))
+    }
+
+    fn index(&self, block: IVec2) -> Option<usize> {
+        let col = u8::try_from(block.x).ok()?;
+        let row = u8::try_from(block.y).ok()?;
+
+        if col < self.size.width() && row < self.size.height() {
+            Some(usize::from(row) * usize::from(self.size.width()) + usize::from(col))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nominos::DEG_90;
+
+    #[test]
+    fn rotated_piece_covers_rotated_blocks() {
+        let grid = BagGrid::new(BagSize::new(3, 4));
+        let piece = Transform::from_xyz(1.5, 0.5, 0.).with_rotation(*DEG_90);
+
+        let blocks = grid.piece_blocks(Vec3::new(1.5, 2., 0.), Nomino::TrominoStraight, piece);
+
+        assert_eq!(
+            blocks.as_slice(),
+            [IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn pieces_cannot_poke_through_walls_or_floor() {
+        let grid = BagGrid::new(BagSize::new(3, 4));
+
+        assert!(grid.straddles(&[IVec2::new(-1, 1), IVec2::new(0, 1)]));
+        assert!(grid.straddles(&[IVec2::new(2, 1), IVec2::new(3, 1)]));
+        assert!(grid.straddles(&[IVec2::new(1, -1), IVec2::new(1, 0)]));
+        assert!(!grid.straddles(&[IVec2::new(2, 4), IVec2::new(3, 4)]));
+        assert!(!grid.straddles(&[IVec2::new(1, 0), IVec2::new(2, 0)]));
+    }
+
+    #[test]
+    fn pieces_must_be_supported() {
+        let mut grid = BagGrid::new(BagSize::new(3, 4));
+        grid.insert(Entity::from_raw(0), NominoColor::Gold, &[IVec2::new(0, 0)]);
+
+        assert!(grid.supports(&[IVec2::new(1, 0)]));
+        assert!(grid.supports(&[IVec2::new(0, 1)]));
+        assert!(!grid.supports(&[IVec2::new(1, 1)]));
+        assert!(grid.overlaps(&[IVec2::new(1, 1), IVec2::new(0, 0)]));
+    }
+
+    #[test]
+    fn removing_a_piece_only_empties_its_cells() {
+        let mut grid = BagGrid::new(BagSize::new(3, 4));
+        grid.insert(Entity::from_raw(0), NominoColor::Gold, &[IVec2::new(0, 0)]);
+        grid.insert(
+            Entity::from_raw(1),
+            NominoColor::Pink,
+            &[IVec2::new(1, 0), IVec2::new(1, 1)],
+        );
+
+        grid.remove(Entity::from_raw(1));
+
+        assert!(grid.get(IVec2::new(0, 0)).is_some());
+        assert!(!grid.overlaps(&[IVec2::new(1, 0), IVec2::new(1, 1)]));
+    }
+
+    #[test]
+    fn sticking_out_of_the_top_fills_the_bag() {
+        let mut grid = BagGrid::new(BagSize::new(3, 4));
+        grid.insert(Entity::from_raw(0), NominoColor::Gold, &[IVec2::new(0, 3)]);
+        assert!(!grid.is_filled());
+
+        grid.insert(
+            Entity::from_raw(0),
+            NominoColor::Gold,
+            &[IVec2::new(1, 3), IVec2::new(1, 4)],
+        );
+        assert!(grid.is_filled());
+        assert_eq!(
+            grid.colors()[3].as_slice(),
+            [Some(NominoColor::Gold), Some(NominoColor::Gold), None]
+        );
+    }
+}
@@ -3,16 +3,18 @@ use bevy::prelude::*;
 use bag_replacement::BagReplacementPlugin;
 pub use bag_replacement::{
     BagChangeDetectionSystems, BagChanged, BagFilled, BagReplacementDetectionSystems,
-    BagReplacementSystems,
+    BagReplacementSystems, Exiting,
 };
 pub use bag_size::BagSize;
 pub use consts::*;
+pub use grid::{BagCell, BagColors, BagGrid, BlockPositions};
 use positioning::BagPositioningPlugin;
 pub use spawn::{BagContainerSpawner, BagMarker, BagSpawner};
 
 mod bag_replacement;
 mod bag_size;
 mod consts;
+mod grid;
 mod positioning;
 mod spawn;
 
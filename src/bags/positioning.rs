@@ -73,7 +73,7 @@ fn transfer_piece_ownership(
         (With<NominoMarker>, Without<BagMarker>),
     >,
 ) {
-    for PiecePlaced { piece, bag } in piece_placements.iter() {
+    for PiecePlaced { piece, bag, .. } in piece_placements.iter() {
         commands.entity(*bag).add_child(*piece);
 
         let bag_global = bag_positions.get(*bag).unwrap().translation;
@@ -1,15 +1,16 @@
-use bevy::{ecs::system::EntityCommands, math::const_vec3, prelude::*};
+use bevy::{ecs::system::EntityCommands, prelude::*};
 use bevy_prototype_lyon::prelude::{
     tess::{geom::Point, path::path::Builder},
     FillMode, *,
 };
-use bevy_rapier3d::prelude::*;
 use smallvec::SmallVec;
 
 use crate::{
     animations,
     animations::GameSpeed,
-    bags::{bag_size::BagSize, consts::*, positioning::compute_container_coordinates},
+    bags::{
+        bag_size::BagSize, consts::*, grid::BagGrid, positioning::compute_container_coordinates,
+    },
     levels::LevelMarker,
     window_management::DipsWindow,
 };
@@ -20,15 +21,6 @@ pub struct BagContainerMarker;
 #[derive(Component)]
 pub struct BagMarker;
 
-#[derive(Component)]
-pub struct BagLidMarker;
-
-#[derive(Component)]
-pub struct BagWallsMarker;
-
-#[derive(Component)]
-pub struct BagFloorMarker;
-
 pub trait BagContainerSpawner {
     fn spawn_bag<'a>(
         &mut self,
@@ -115,6 +107,7 @@ fn spawn_bag<'w, 's, 'a>(
     ));
     commands.insert(BagMarker);
     commands.insert(bag_size);
+    commands.insert(BagGrid::new(bag_size));
     commands.insert(animations::bag_enter(
         entry_transform,
         transform,
@@ -122,54 +115,6 @@ fn spawn_bag<'w, 's, 'a>(
         is_replacement,
     ));
 
-    commands.insert(Collider::cuboid(
-        bag_size.half_width(),
-        bag_size.half_height(),
-        0.,
-    ));
-    commands.insert(Sensor(true));
-    commands.insert(BAG_COLLIDER_GROUP);
-    commands.with_children(|parent| {
-        parent
-            .spawn_bundle(TransformBundle::default())
-            .insert(BagLidMarker)
-            .insert(Collider::compound(vec![(
-                const_vec3!([0., bag_size.half_height() + LID_OFFSET, 0.]),
-                Quat::IDENTITY,
-                Collider::cuboid(bag_size.half_width(), LID_HALFHEIGHT, 0.),
-            )]))
-            .insert(Sensor(true))
-            .insert(BAG_LID_COLLIDER_GROUP);
-
-        parent
-            .spawn_bundle(TransformBundle::default())
-            .insert(BagWallsMarker)
-            .insert(Collider::compound(vec![
-                (
-                    const_vec3!([-bag_size.half_width(), 0., 0.]),
-                    Quat::IDENTITY,
-                    Collider::cuboid(BOUNDARY_HALFWIDTH, bag_size.half_height(), 0.),
-                ),
-                (
-                    const_vec3!([bag_size.half_width(), 0., 0.]),
-                    Quat::IDENTITY,
-                    Collider::cuboid(BOUNDARY_HALFWIDTH, bag_size.half_height(), 0.),
-                ),
-            ]))
-            .insert(Sensor(true))
-            .insert(BAG_WALLS_COLLIDER_GROUP);
-
-        parent
-            .spawn_bundle(TransformBundle::default())
-            .insert(BagFloorMarker)
-            .insert(Collider::compound(vec![(
-                const_vec3!([0., -bag_size.half_height(), 0.]),
-                Quat::IDENTITY,
-                Collider::cuboid(bag_size.half_width(), BOUNDARY_HALFWIDTH, 0.),
-            )]))
-            .insert(Sensor(true))
-            .insert(BAG_FLOOR_COLLIDER_GROUP);
-    });
     commands
 }
 
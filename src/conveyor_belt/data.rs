@@ -1,11 +1,22 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
-use num_traits::FromPrimitive;
-use rand::{thread_rng, Rng};
+use gb9000_level_generation::{random_tiling, Shape};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 
-use crate::nominos::{Nomino, NominoColor, DEG_180, DEG_MIRRORED};
+use crate::{
+    bags::BagSize,
+    nominos::{Nomino, NominoColor, DEG_180, DEG_MIRRORED},
+};
 
 pub trait ConveyorBelt {
     fn next(&mut self) -> Option<Piece>;
+
+    /// Called for every bag put on screen, including replacements.
+    fn bag_opened(&mut self, _size: BagSize) {}
+
+    /// Called for every bag that starts leaving the screen.
+    fn bag_closed(&mut self, _size: BagSize) {}
 }
 
 #[derive(Copy, Clone)]
@@ -16,57 +27,219 @@ pub struct Piece {
     pub rotation: Quat,
 }
 
-pub struct PresetPiecesConveyorBelt<const N: usize> {
-    pieces: [Piece; N],
+impl Piece {
+    /// Lays out a shape from the level generator, optionally upside down.
+    #[must_use]
+    pub fn from_shape(shape: Shape, color: NominoColor, upside_down: bool) -> Self {
+        let mut rotation = if shape.mirrored {
+            *DEG_MIRRORED
+        } else {
+            default()
+        };
+        if upside_down {
+            rotation *= *DEG_180;
+        }
+
+        Self {
+            nomino: shape.nomino,
+            color,
+            rotation,
+        }
+    }
+}
+
+pub struct PresetPiecesConveyorBelt {
+    pieces: Vec<Piece>,
     next: usize,
 }
 
-impl<const N: usize> PresetPiecesConveyorBelt<N> {
-    pub const fn new(pieces: [Piece; N]) -> Self {
-        Self { pieces, next: 0 }
+impl PresetPiecesConveyorBelt {
+    pub fn new(pieces: impl Into<Vec<Piece>>) -> Self {
+        Self {
+            pieces: pieces.into(),
+            next: 0,
+        }
     }
 }
 
-impl<const N: usize> ConveyorBelt for PresetPiecesConveyorBelt<N> {
+impl ConveyorBelt for PresetPiecesConveyorBelt {
     fn next(&mut self) -> Option<Piece> {
-        if self.next >= N {
-            return None;
+        let piece = self.pieces.get(self.next).copied();
+        if piece.is_some() {
+            self.next += 1;
         }
+        piece
+    }
+}
+
+pub struct InfinitePiecesConveyorBelt {
+    colors: Vec<NominoColor>,
+    rng: StdRng,
+}
 
-        let piece = self.pieces[self.next];
-        self.next += 1;
-        Some(piece)
+impl InfinitePiecesConveyorBelt {
+    /// # Panics
+    ///
+    /// If `colors` is empty.
+    pub fn new(colors: impl Into<Vec<NominoColor>>, seed: u64) -> Self {
+        let colors = colors.into();
+        assert!(!colors.is_empty(), "infinite belts need at least one color");
+        Self {
+            colors,
+            rng: StdRng::seed_from_u64(seed),
+        }
     }
 }
 
-pub struct InfinitePiecesConveyorBelt<const COLORS: usize> {
-    colors: [NominoColor; COLORS],
+impl ConveyorBelt for InfinitePiecesConveyorBelt {
+    fn next(&mut self) -> Option<Piece> {
+        let rng = &mut self.rng;
+        let next_shape = rng.gen_range(0..Nomino::ALL.len());
+        let next_color = rng.gen_range(0..self.colors.len());
+
+        Some(Piece {
+            nomino: Nomino::ALL[next_shape],
+            color: self.colors[next_color],
+            rotation: random_rotation(rng),
+        })
+    }
 }
 
-impl<const COLORS: usize> InfinitePiecesConveyorBelt<COLORS> {
-    pub const fn new(colors: [NominoColor; COLORS]) -> Self {
-        Self { colors }
+/// Deals every shape once in a random order before starting over, so no shape
+/// can go missing for long. Colors are dealt the same way from `colors`, which
+/// may repeat a color to make it more common.
+pub struct ShuffledPiecesConveyorBelt {
+    colors: Vec<NominoColor>,
+    rng: StdRng,
+    nomino_bag: Vec<Nomino>,
+    color_bag: Vec<NominoColor>,
+}
+
+impl ShuffledPiecesConveyorBelt {
+    /// # Panics
+    ///
+    /// If `colors` is empty.
+    pub fn new(colors: impl Into<Vec<NominoColor>>, seed: u64) -> Self {
+        let colors = colors.into();
+        assert!(!colors.is_empty(), "shuffled belts need at least one color");
+        Self {
+            colors,
+            rng: StdRng::seed_from_u64(seed),
+            nomino_bag: Vec::with_capacity(Nomino::ALL.len()),
+            color_bag: Vec::new(),
+        }
     }
 }
 
-impl<const COLORS: usize> ConveyorBelt for InfinitePiecesConveyorBelt<COLORS> {
+impl ConveyorBelt for ShuffledPiecesConveyorBelt {
     fn next(&mut self) -> Option<Piece> {
-        let mut rng = thread_rng();
-        let next_shape = rng.gen_range(0..Nomino::_Last as usize);
-        let next_color = rng.gen_range(0..COLORS);
-        let mut rotation = if rng.gen() { *DEG_MIRRORED } else { default() };
-        if rng.gen() {
-            rotation *= *DEG_180;
+        if self.nomino_bag.is_empty() {
+            self.nomino_bag.extend_from_slice(&Nomino::ALL);
+            self.nomino_bag.shuffle(&mut self.rng);
+        }
+        if self.color_bag.is_empty() {
+            self.color_bag.extend_from_slice(&self.colors);
+            self.color_bag.shuffle(&mut self.rng);
         }
 
         Some(Piece {
-            nomino: Nomino::from_usize(next_shape).unwrap(),
-            color: self.colors[next_color],
-            rotation,
+            nomino: self.nomino_bag.pop().unwrap(),
+            color: self.color_bag.pop().unwrap(),
+            rotation: random_rotation(&mut self.rng),
+        })
+    }
+}
+
+/// Deals the pieces of a random perfect filling for each bag put on screen,
+/// so the bags can always be completely filled with the upcoming pieces.
+/// Each filling uses a single color. Once every new bag has its filling, the
+/// bags still on screen take turns getting another one. Random pieces are
+/// only dealt when none of the bags on screen can be perfectly filled.
+pub struct FillableBagsConveyorBelt {
+    colors: Vec<NominoColor>,
+    rng: StdRng,
+    opened: VecDeque<BagSize>,
+    on_screen: Vec<BagSize>,
+    next_refill: usize,
+    pending: Vec<Piece>,
+    fallback: InfinitePiecesConveyorBelt,
+}
+
+impl FillableBagsConveyorBelt {
+    /// # Panics
+    ///
+    /// If `colors` is empty.
+    pub fn new(colors: impl Into<Vec<NominoColor>>, seed: u64) -> Self {
+        let colors = colors.into();
+        assert!(!colors.is_empty(), "fillable belts need at least one color");
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self {
+            fallback: InfinitePiecesConveyorBelt::new(colors.clone(), rng.gen()),
+            colors,
+            rng,
+            opened: VecDeque::new(),
+            on_screen: Vec::new(),
+            next_refill: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    fn next_bag_to_fill(&mut self) -> BagSize {
+        self.opened.pop_front().unwrap_or_else(|| {
+            let size = self.on_screen[self.next_refill % self.on_screen.len()];
+            self.next_refill += 1;
+            size
         })
     }
 }
 
+impl ConveyorBelt for FillableBagsConveyorBelt {
+    fn next(&mut self) -> Option<Piece> {
+        // Give up on refills after trying every bag on screen once
+        let mut attempts = self.opened.len() + self.on_screen.len();
+        while self.pending.is_empty() && attempts > 0 {
+            attempts -= 1;
+            let size = self.next_bag_to_fill();
+            let rng = &mut self.rng;
+            let Some(tiling) = random_tiling(size.width(), size.height(), rng) else {
+                continue;
+            };
+
+            let color = *self.colors.choose(rng).unwrap();
+            self.pending.extend(
+                tiling
+                    .into_iter()
+                    .map(|shape| Piece::from_shape(shape, color, rng.gen())),
+            );
+            self.pending.shuffle(rng);
+        }
+        self.pending.pop().or_else(|| self.fallback.next())
+    }
+
+    fn bag_opened(&mut self, size: BagSize) {
+        self.opened.push_back(size);
+        self.on_screen.push(size);
+    }
+
+    fn bag_closed(&mut self, size: BagSize) {
+        // Bags leaving before their filling was dealt don't need it anymore
+        if let Some(index) = self.opened.iter().position(|&open| open == size) {
+            self.opened.remove(index);
+        }
+        if let Some(index) = self.on_screen.iter().position(|&open| open == size) {
+            self.on_screen.remove(index);
+        }
+    }
+}
+
+fn random_rotation(rng: &mut impl Rng) -> Quat {
+    let mut rotation = if rng.gen() { *DEG_MIRRORED } else { default() };
+    if rng.gen() {
+        rotation *= *DEG_180;
+    }
+    rotation
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +277,84 @@ mod tests {
         assert_eq!(belt.next(), Some(pieces[2]));
         assert_eq!(belt.next(), None);
     }
+
+    #[test]
+    fn infinite_pieces_are_reproducible_from_their_seed() {
+        let colors = [NominoColor::Gold, NominoColor::Pink, NominoColor::Blue];
+        let mut belt = InfinitePiecesConveyorBelt::new(colors, 42);
+        let mut replay = InfinitePiecesConveyorBelt::new(colors, 42);
+        let mut other = InfinitePiecesConveyorBelt::new(colors, 43);
+
+        let pieces = (0..20).map(|_| belt.next()).collect::<Vec<_>>();
+        assert_eq!(pieces, (0..20).map(|_| replay.next()).collect::<Vec<_>>());
+        assert_ne!(pieces, (0..20).map(|_| other.next()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shuffled_pieces_deal_every_shape_before_repeating() {
+        let shapes = Nomino::ALL.len();
+        let colors = [NominoColor::Gold, NominoColor::Gold, NominoColor::Pink];
+        let mut belt = ShuffledPiecesConveyorBelt::new(colors, 42);
+
+        let pieces = (0..shapes * colors.len())
+            .map(|_| belt.next().unwrap())
+            .collect::<Vec<_>>();
+
+        for deal in pieces.chunks(shapes) {
+            let mut nominos = deal.iter().map(|p| p.nomino).collect::<Vec<_>>();
+            nominos.sort_unstable();
+            assert_eq!(nominos, Nomino::ALL);
+        }
+        for deal in pieces.chunks(colors.len()) {
+            let golds = deal.iter().filter(|p| p.color == NominoColor::Gold).count();
+            assert_eq!(golds, 2);
+        }
+    }
+
+    #[test]
+    fn fillable_bags_get_exactly_enough_blocks() {
+        let sizes = [BagSize::new(3, 4), BagSize::new(4, 2), BagSize::new(3, 4)];
+        let mut belt = FillableBagsConveyorBelt::new([NominoColor::Gold], 42);
+        for size in sizes {
+            belt.bag_opened(size);
+        }
+
+        for size in sizes {
+            let mut blocks = 0;
+            while blocks < size.capacity() {
+                blocks += belt.next().unwrap().nomino.block_count();
+            }
+
+            assert_eq!(blocks, size.capacity());
+        }
+    }
+
+    #[test]
+    fn bags_on_screen_keep_getting_fillings() {
+        let size = BagSize::new(3, 3);
+        let mut belt = FillableBagsConveyorBelt::new([NominoColor::Gold], 42);
+        belt.bag_opened(BagSize::new(1, 1));
+        belt.bag_opened(size);
+        belt.bag_opened(BagSize::new(4, 2));
+        belt.bag_closed(BagSize::new(4, 2));
+
+        // The first filling is for the new bag, the rest are refills
+        for _ in 0..3 {
+            let mut blocks = 0;
+            while blocks < size.capacity() {
+                blocks += belt.next().unwrap().nomino.block_count();
+            }
+
+            assert_eq!(blocks, size.capacity());
+        }
+    }
+
+    #[test]
+    fn unfillable_bags_fall_back_to_random_pieces() {
+        let mut belt = FillableBagsConveyorBelt::new([NominoColor::Gold], 42);
+        belt.bag_opened(BagSize::new(1, 1));
+
+        assert!(belt.next().is_some());
+        assert!(belt.next().is_some());
+    }
 }
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use rand::{thread_rng, Rng};
 
 pub use consts::{HEIGHT, MAX_NUM_PIECES};
 pub use data::*;
 use movement::ConveyorBeltMovementPlugin;
-pub use movement::{BeltEmptyEvent, BeltMovementSystems};
+pub use movement::{BeltEmptyEvent, BeltMovementSystems, BeltPieceIds};
 use positioning::ConveyorBeltPositioningPlugin;
-pub use spawn::{BoxedConveyorBelt, ConveyorBeltSpawner};
+pub use spawn::{BoxedConveyorBelt, ConveyorBeltMarker, ConveyorBeltSpawner};
 
 mod consts;
 mod data;
@@ -18,6 +19,7 @@ pub struct ConveyorBeltPlugin;
 impl Plugin for ConveyorBeltPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ConveyorBeltOptions>();
+        app.init_resource::<BeltSeed>();
 
         app.add_plugin(ConveyorBeltMovementPlugin);
         app.add_plugin(ConveyorBeltPositioningPlugin);
@@ -35,3 +37,19 @@ impl Default for ConveyorBeltOptions {
         }
     }
 }
+
+/// Drives every random choice made by infinite belts so runs can be replayed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deref, DerefMut)]
+pub struct BeltSeed(pub u64);
+
+impl Default for BeltSeed {
+    fn default() -> Self {
+        Self(thread_rng().gen())
+    }
+}
+
+impl BeltSeed {
+    pub fn reroll(&mut self) {
+        *self = default();
+    }
+}
@@ -5,7 +5,8 @@ use smallvec::SmallVec;
 
 use crate::{
     animations,
-    animations::GameSpeed,
+    animations::{AnimationComponentsBundle, GameSpeed},
+    bags::{BagMarker, BagSize, Exiting},
     conveyor_belt::{
         consts::{
             LENGTH, MAX_NUM_PIECES, NON_SELECTABLE_LIGHTNESS, PIECE_WIDTH, SELECTABLE_SEPARATION,
@@ -16,16 +17,17 @@ use crate::{
             BeltNonselectableBackgroundMarker, BeltSelectableBackgroundMarker,
             ConveyorBeltBackgroundSpawner, ConveyorBeltInstance, ConveyorBeltMarker,
         },
-        ConveyorBelt, ConveyorBeltOptions,
+        ConveyorBelt, ConveyorBeltOptions, Piece,
     },
-    gb9000::GroceryBagger9000,
+    gb9000::{GameState::LevelStarting, GroceryBagger9000},
     levels::{LevelStarted, LevelTransitionSystems},
     nominos::{
-        NominoColor, NominoMarker, NominoSpawner, OutOfBagPlacement, PiecePickedUp, PiecePlaced,
-        PieceSystems, Selectable, Selected, DEG_90, DEG_MIRRORED,
+        Nomino, NominoColor, NominoMarker, NominoSpawner, OutOfBagPlacement, PiecePickedUp,
+        PiecePlaced, PieceSystems, PieceUndoSystems, PlacementUndone, Selectable, Selected, DEG_90,
+        DEG_MIRRORED,
     },
     robot::RobotTargetMarker,
-    run_criteria::run_if_level_started,
+    run_criteria::run_on_exit,
     ui::MenuButtonClickedSystems,
     window_management::WindowSystems,
 };
@@ -36,9 +38,11 @@ impl Plugin for ConveyorBeltMovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<BeltEmptyEvent>();
 
+        app.add_system_to_stage(CoreStage::PostUpdate, open_bags.before(init_pieces));
+        app.add_system_to_stage(CoreStage::PostUpdate, close_bags.before(open_bags));
         app.add_system_to_stage(
             CoreStage::PostUpdate,
-            init_pieces.with_run_criteria(run_if_level_started),
+            init_pieces.with_run_criteria(run_on_exit(&[LevelStarting])),
         );
         app.add_system(
             replace_pieces
@@ -55,12 +59,19 @@ impl Plugin for ConveyorBeltMovementPlugin {
                 .before(LevelTransitionSystems)
                 .after(replace_pieces),
         );
+        app.add_system(
+            return_undone_pieces
+                .label(BeltMovementSystems)
+                .after(PieceUndoSystems)
+                .after(replace_pieces),
+        );
         app.add_system(
             move_pieces
                 .label(BeltMovementSystems)
                 .after(LevelTransitionSystems)
                 .after(WindowSystems)
-                .after(replace_pieces),
+                .after(replace_pieces)
+                .after(return_undone_pieces),
         );
         app.add_system(
             update_robot_target_on_piece_selection
@@ -87,6 +98,28 @@ pub struct BeltEmptyEvent;
 #[derive(Default, Component, Deref, DerefMut)]
 pub struct BeltPieceIds(SmallVec<[Entity; MAX_NUM_PIECES as usize]>);
 
+fn open_bags(
+    mut conveyor_belt: Query<&mut ConveyorBeltInstance, With<ConveyorBeltMarker>>,
+    opened_bags: Query<&BagSize, Added<BagMarker>>,
+) {
+    for mut conveyor_belt in conveyor_belt.iter_mut() {
+        for size in opened_bags.iter() {
+            conveyor_belt.bag_opened(*size);
+        }
+    }
+}
+
+fn close_bags(
+    mut conveyor_belt: Query<&mut ConveyorBeltInstance, With<ConveyorBeltMarker>>,
+    closed_bags: Query<&BagSize, (With<BagMarker>, Added<Exiting>)>,
+) {
+    for mut conveyor_belt in conveyor_belt.iter_mut() {
+        for size in closed_bags.iter() {
+            conveyor_belt.bag_closed(*size);
+        }
+    }
+}
+
 fn init_pieces(
     mut commands: Commands,
     mut conveyor_belt: Query<
@@ -105,7 +138,7 @@ fn init_pieces(
             start,
             i,
             id,
-            &mut ***conveyor_belt,
+            &mut *conveyor_belt,
             &belt_options,
         );
 
@@ -192,7 +225,7 @@ fn replace_pieces(
             from,
             position,
             id,
-            &mut ***conveyor_belt,
+            &mut *conveyor_belt,
             &belt_options,
         );
         if let Some(spawned) = spawned {
@@ -270,6 +303,70 @@ fn check_for_piece_selection_undos(
     }
 }
 
+fn return_undone_pieces(
+    mut commands: Commands,
+    mut undone_placements: EventReader<PlacementUndone>,
+    mut conveyor_belt: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut ConveyorBeltInstance,
+            &mut BeltPieceIds,
+        ),
+        With<ConveyorBeltMarker>,
+    >,
+    mut pieces: Query<
+        (&GlobalTransform, &mut Transform, &Nomino, &NominoColor),
+        (With<NominoMarker>, Without<ConveyorBeltMarker>),
+    >,
+    old_robot_target: Query<Entity, With<RobotTargetMarker>>,
+    selected_pieces: Query<(), With<Selected>>,
+    nomino_colors: Query<&NominoColor, With<NominoMarker>>,
+    mut colors: Query<&mut DrawMode, With<NominoMarker>>,
+    belt_options: Res<ConveyorBeltOptions>,
+) {
+    for PlacementUndone { piece, .. } in undone_placements.iter() {
+        let (id, belt_position, mut conveyor_belt, mut belt_pieces) = conveyor_belt.single_mut();
+
+        // The belt slides the piece over from where it was in the bag
+        let (piece_position, mut transform, ..) = pieces.get_mut(*piece).unwrap();
+        transform.translation = piece_position.translation - belt_position.translation;
+        transform.scale = Vec3::ONE;
+        commands.entity(id).add_child(*piece);
+        commands
+            .entity(*piece)
+            .remove_bundle::<AnimationComponentsBundle<Transform>>();
+        belt_pieces.insert(0, *piece);
+
+        for id in old_robot_target.iter() {
+            commands.entity(id).remove::<RobotTargetMarker>();
+        }
+        commands.entity(*piece).insert(RobotTargetMarker);
+
+        // Keep the belt from overflowing by dealing its last piece again later
+        if belt_pieces.len() > usize::from(MAX_NUM_PIECES) &&
+        let Some(index) = belt_pieces.iter().rposition(|id| !selected_pieces.contains(*id))
+        {
+            let last = belt_pieces.remove(index);
+            let (_, transform, nomino, color) = pieces.get(last).unwrap();
+            conveyor_belt.push_back(Piece {
+                nomino: *nomino,
+                color: *color,
+                rotation: transform.rotation,
+            });
+            commands.entity(last).despawn_recursive();
+        }
+
+        update_piece_selectability(
+            &mut commands,
+            &belt_pieces,
+            &belt_options,
+            &nomino_colors,
+            &mut colors,
+        );
+    }
+}
+
 fn update_robot_target_on_piece_selection(
     mut commands: Commands,
     belt_pieces: Query<&BeltPieceIds, With<ConveyorBeltMarker>>,
@@ -327,13 +424,25 @@ fn update_piece_selectability_on_num_selectable_pieces_changed(
     nomino_colors: Query<&NominoColor, With<NominoMarker>>,
     mut colors: Query<&mut DrawMode, With<NominoMarker>>,
 ) {
-    let num_pieces_selectable = belt_options.num_pieces_selectable as usize;
-    let belt_pieces = if let Ok(belt_pieces) = conveyor_belt.get_single() {
-        belt_pieces
-    } else {
-        return;
-    };
+    if let Ok(belt_pieces) = conveyor_belt.get_single() {
+        update_piece_selectability(
+            &mut commands,
+            belt_pieces,
+            &belt_options,
+            &nomino_colors,
+            &mut colors,
+        );
+    }
+}
 
+fn update_piece_selectability(
+    commands: &mut Commands,
+    belt_pieces: &[Entity],
+    belt_options: &ConveyorBeltOptions,
+    nomino_colors: &Query<&NominoColor, With<NominoMarker>>,
+    colors: &mut Query<&mut DrawMode, With<NominoMarker>>,
+) {
+    let num_pieces_selectable = belt_options.num_pieces_selectable as usize;
     for (index, piece) in belt_pieces.iter().enumerate() {
         let mut draw_mode = colors.get_mut(*piece).unwrap();
         if let DrawMode::Outlined {
@@ -8,6 +8,7 @@ use bevy_prototype_lyon::{
 };
 
 use crate::{
+    bags::BagSize,
     conveyor_belt::{
         consts::{
             BELT_NONSELECTABLE_BACKGROUND_COLOR, BELT_SELECTABLE_BACKGROUND_COLOR, PIECE_WIDTH,
@@ -15,7 +16,7 @@ use crate::{
         },
         movement::BeltPieceIds,
         positioning::{compute_belt_position, compute_selectable_background},
-        ConveyorBelt, HEIGHT, MAX_NUM_PIECES,
+        ConveyorBelt, Piece, HEIGHT, MAX_NUM_PIECES,
     },
     levels::LevelMarker,
     window_management::DipsWindow,
@@ -32,8 +33,32 @@ pub struct BeltSelectableBackgroundMarker;
 #[derive(Component)]
 pub struct BeltNonselectableBackgroundMarker;
 
-#[derive(Component, Deref, DerefMut)]
-pub struct ConveyorBeltInstance(BoxedConveyorBelt);
+#[derive(Component)]
+pub struct ConveyorBeltInstance {
+    belt: BoxedConveyorBelt,
+    /// Pieces pushed off the end of the belt, dealt again before new ones.
+    returned: Vec<Piece>,
+}
+
+impl ConveyorBeltInstance {
+    pub fn push_back(&mut self, piece: Piece) {
+        self.returned.push(piece);
+    }
+}
+
+impl ConveyorBelt for ConveyorBeltInstance {
+    fn next(&mut self) -> Option<Piece> {
+        self.returned.pop().or_else(|| self.belt.next())
+    }
+
+    fn bag_opened(&mut self, size: BagSize) {
+        self.belt.bag_opened(size);
+    }
+
+    fn bag_closed(&mut self, size: BagSize) {
+        self.belt.bag_closed(size);
+    }
+}
 
 pub trait ConveyorBeltSpawner<'w, 's> {
     fn spawn_belt(&mut self, dips_window: &DipsWindow, belt: BoxedConveyorBelt);
@@ -57,7 +82,10 @@ impl<'w, 's> ConveyorBeltSpawner<'w, 's> for Commands<'w, 's> {
     fn spawn_belt(&mut self, dips_window: &DipsWindow, belt: BoxedConveyorBelt) {
         self.spawn_and_forget(ConveyorBeltBundle {
             transforms: TransformBundle::from_transform(compute_belt_position(dips_window)),
-            conveyor_belt: ConveyorBeltInstance(belt),
+            conveyor_belt: ConveyorBeltInstance {
+                belt,
+                returned: Vec::new(),
+            },
             level_marker: default(),
             belt_marker: default(),
             pieces: default(),
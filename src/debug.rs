@@ -5,15 +5,16 @@ use bevy_inspector_egui_rapier::InspectableRapierPlugin;
 use bevy_screen_diags::ScreenDiagsPlugin;
 
 use crate::{
+    actions::{Action, Actions},
     animations::GameSpeed,
-    conveyor_belt::ConveyorBeltOptions,
+    conveyor_belt::{BeltSeed, ConveyorBeltOptions},
     gb9000::{
-        GameState::{LevelEnded, Playing},
+        GameState::{LevelEnded, LevelStarting, Playing},
         GroceryBagger9000,
     },
     levels::{LevelFinished, LevelMarker},
     nominos::{NominoColor, *},
-    robot::RobotOptions,
+    robot::{PlacementStrategy, RobotDifficulty, RobotOptions},
 };
 
 pub struct DebugPlugin;
@@ -37,15 +38,13 @@ impl Plugin for DebugPlugin {
             CoreStage::PreUpdate,
             debug_options.after(EguiSystem::BeginFrame),
         );
-        app.add_system(
-            open_debug_menu.with_run_criteria(|keys: Res<Input<KeyCode>>| {
-                if keys.just_pressed(KeyCode::Semicolon) {
-                    ShouldRun::Yes
-                } else {
-                    ShouldRun::No
-                }
-            }),
-        );
+        app.add_system(open_debug_menu.with_run_criteria(|actions: Res<Actions>| {
+            if actions.just_pressed(Action::DebugMenu) {
+                ShouldRun::Yes
+            } else {
+                ShouldRun::No
+            }
+        }));
     }
 }
 
@@ -112,6 +111,7 @@ fn debug_options(
     mut commands: Commands,
     mut gb9000: ResMut<GroceryBagger9000>,
     mut conveyor_belt_options: ResMut<ConveyorBeltOptions>,
+    mut belt_seed: ResMut<BeltSeed>,
     mut robot_options: ResMut<RobotOptions>,
     mut game_speed: ResMut<GameSpeed>,
     mut level_finished: EventWriter<LevelFinished>,
@@ -132,11 +132,11 @@ fn debug_options(
 
                 if level_num != gb9000.current_level {
                     gb9000.current_level = level_num;
-                    gb9000.state = Playing;
+                    gb9000.state = LevelStarting;
                     level_finished.send(LevelFinished);
                 }
 
-                if gb9000.state != LevelEnded && ui.button("Finish").clicked() {
+                if gb9000.state == Playing && ui.button("Finish").clicked() {
                     gb9000.state = LevelEnded;
                     level_finished.send(LevelFinished);
                 }
@@ -165,7 +165,46 @@ fn debug_options(
                 );
             });
 
-            ui.checkbox(&mut robot_options.enabled, "Enable robot");
+            ui.horizontal(|ui| {
+                ui.label("Belt seed");
+                ui.add(egui::DragValue::new(&mut **belt_seed));
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut robot_options.enabled, "Enable robot");
+
+                egui::ComboBox::from_id_source("Robot strategy")
+                    .selected_text(robot_options.strategy.name())
+                    .show_ui(ui, |ui| {
+                        for strategy in [
+                            PlacementStrategy::Greedy,
+                            PlacementStrategy::ScoreMaximizing,
+                            PlacementStrategy::Adversarial,
+                        ] {
+                            ui.selectable_value(
+                                &mut robot_options.strategy,
+                                strategy,
+                                strategy.name(),
+                            );
+                        }
+                    });
+
+                egui::ComboBox::from_id_source("Robot difficulty")
+                    .selected_text(robot_options.difficulty.name())
+                    .show_ui(ui, |ui| {
+                        for difficulty in [
+                            RobotDifficulty::Easy,
+                            RobotDifficulty::Normal,
+                            RobotDifficulty::Hard,
+                        ] {
+                            ui.selectable_value(
+                                &mut robot_options.difficulty,
+                                difficulty,
+                                difficulty.name(),
+                            );
+                        }
+                    });
+            });
 
             ui.separator();
             ui.horizontal(|ui| {
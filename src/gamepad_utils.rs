@@ -0,0 +1,85 @@
+use bevy::{math::const_ivec2, prelude::*};
+
+const STICK_THRESHOLD: f32 = 0.5;
+const STICK_REPEAT_SECS: f32 = 0.15;
+
+const DPAD: [(GamepadButtonType, IVec2); 4] = [
+    (GamepadButtonType::DPadLeft, const_ivec2!([-1, 0])),
+    (GamepadButtonType::DPadRight, const_ivec2!([1, 0])),
+    (GamepadButtonType::DPadUp, const_ivec2!([0, 1])),
+    (GamepadButtonType::DPadDown, const_ivec2!([0, -1])),
+];
+
+/// Whether the button was just pressed on any connected gamepad.
+pub fn gamepad_just_pressed(
+    gamepads: &Gamepads,
+    buttons: &Input<GamepadButton>,
+    button: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| buttons.just_pressed(GamepadButton(*gamepad, button)))
+}
+
+/// Turns the D-pad and left stick into steps on a grid. Holding the stick
+/// keeps stepping in its direction.
+pub struct GamepadDirection {
+    held: IVec2,
+    repeat: Timer,
+}
+
+impl Default for GamepadDirection {
+    fn default() -> Self {
+        Self {
+            held: IVec2::ZERO,
+            repeat: Timer::from_seconds(STICK_REPEAT_SECS, true),
+        }
+    }
+}
+
+impl GamepadDirection {
+    pub fn step(
+        &mut self,
+        time: &Time,
+        gamepads: &Gamepads,
+        buttons: &Input<GamepadButton>,
+        axes: &Axis<GamepadAxis>,
+    ) -> IVec2 {
+        let mut step = DPAD
+            .iter()
+            .filter(|(button, _)| gamepad_just_pressed(gamepads, buttons, *button))
+            .fold(IVec2::ZERO, |sum, (_, direction)| sum + *direction);
+
+        let held = gamepads
+            .iter()
+            .map(|gamepad| {
+                let stick = |axis| axes.get(GamepadAxis(*gamepad, axis)).unwrap_or_default();
+                IVec2::new(
+                    stick_direction(stick(GamepadAxisType::LeftStickX)),
+                    stick_direction(stick(GamepadAxisType::LeftStickY)),
+                )
+            })
+            .find(|direction| *direction != IVec2::ZERO)
+            .unwrap_or_default();
+
+        if held != self.held {
+            self.held = held;
+            self.repeat.reset();
+            step += held;
+        } else if held != IVec2::ZERO && self.repeat.tick(time.delta()).just_finished() {
+            step += held;
+        }
+
+        step.signum()
+    }
+}
+
+fn stick_direction(value: f32) -> i32 {
+    if value > STICK_THRESHOLD {
+        1
+    } else if value < -STICK_THRESHOLD {
+        -1
+    } else {
+        0
+    }
+}
@@ -5,16 +5,33 @@ use bevy_svg::prelude::SvgPlugin;
 use bevy_tweening::TweeningPlugin;
 
 use crate::{
-    animations::AnimationPlugin, bags::BagsPlugin, conveyor_belt::ConveyorBeltPlugin,
-    levels::LevelsPlugin, nominos::PiecesPlugin, robot::RobotPlugin, ui::UiPlugin,
+    actions::ActionsPlugin, animations::AnimationPlugin, bags::BagsPlugin,
+    conveyor_belt::ConveyorBeltPlugin, levels::LevelsPlugin, nominos::PiecesPlugin,
+    robot::RobotPlugin, save::SavePlugin, ui::UiPlugin,
 };
 
 pub struct GroceryBagger9000Plugin;
 
 impl Plugin for GroceryBagger9000Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(HeadlessGroceryBagger9000Plugin);
+
+        app.add_plugin(SavePlugin);
+
+        app.add_plugin(ShapePlugin);
+        app.add_plugin(SvgPlugin);
+    }
+}
+
+/// The game without anything that needs a renderer or touches the player's
+/// save, for use with `MinimalPlugins`.
+pub struct HeadlessGroceryBagger9000Plugin;
+
+impl Plugin for HeadlessGroceryBagger9000Plugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GroceryBagger9000>();
 
+        app.add_plugin(ActionsPlugin);
         app.add_plugin(LevelsPlugin);
         app.add_plugin(PiecesPlugin);
         app.add_plugin(BagsPlugin);
@@ -25,22 +42,30 @@ impl Plugin for GroceryBagger9000Plugin {
         #[cfg(not(feature = "debug"))]
         app.add_plugin(crate::analytics::AnalyticsPlugin);
 
-        app.add_plugin(ShapePlugin);
         app.add_plugin(RapierPhysicsPlugin::<NoUserData>::default());
         app.insert_resource(RapierConfiguration {
             physics_pipeline_active: false,
             ..default()
         });
         app.add_plugin(TweeningPlugin);
-        app.add_plugin(SvgPlugin);
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
 pub enum GameState {
+    /// Waiting for the levels to load.
     #[default]
+    Boot,
+    MainMenu,
+    LevelSelect,
+    /// Waiting for the current level to be unloaded and spawned afresh.
+    LevelStarting,
     Playing,
+    Paused,
+    Settings,
     LevelEnded,
+    /// The last level was just finished.
+    GameComplete,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,329 @@
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+};
+use serde::Deserialize;
+
+use crate::{
+    bags::{BagSize, BlockPositions},
+    conveyor_belt::{
+        BeltSeed, BoxedConveyorBelt, FillableBagsConveyorBelt, InfinitePiecesConveyorBelt, Piece,
+        PresetPiecesConveyorBelt, ShuffledPiecesConveyorBelt,
+    },
+    levels::{scoring::CurrentScore, stars::StarThresholds},
+    nominos::{Nomino, NominoColor, NominoGeometry, DEG_180, DEG_90, DEG_MIRRORED},
+    robot::PlacementStrategy,
+};
+
+pub struct LevelDefinitionPlugin;
+
+impl Plugin for LevelDefinitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<LevelDefinition>();
+        app.init_asset_loader::<LevelDefinitionLoader>();
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TypeUuid)]
+#[uuid = "5b7e0b42-6f0c-4d1b-9a3e-2c1f8d4a7e90"]
+pub struct LevelDefinition {
+    pub belt: BeltDefinition,
+    pub bags: Vec<BagDefinition>,
+    #[serde(default)]
+    pub robot: bool,
+    /// Overrides the player's preferred robot strategy.
+    #[serde(default)]
+    pub robot_strategy: Option<PlacementStrategy>,
+    #[serde(default)]
+    pub tutorial: Option<TutorialDefinition>,
+    /// Overrides the thresholds worked out from the level's best possible
+    /// score.
+    #[serde(default)]
+    pub stars: Option<StarThresholds>,
+    /// Lets players flip the piece they're holding.
+    #[serde(default)]
+    pub mirroring: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum BeltDefinition {
+    Preset(Vec<PieceDefinition>),
+    Infinite(Vec<NominoColor>),
+    /// Deals shuffled sets of every shape. Repeat a color to make it more
+    /// common.
+    Shuffled(Vec<NominoColor>),
+    /// Deals pieces that can perfectly fill the bags on screen.
+    Fillable(Vec<NominoColor>),
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub struct PieceDefinition {
+    pub nomino: Nomino,
+    pub color: NominoColor,
+    #[serde(default)]
+    pub rotation: Rotation,
+    #[serde(default)]
+    pub mirrored: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BagDefinition {
+    pub width: u8,
+    pub height: u8,
+    #[serde(default)]
+    pub pieces: Vec<PlacedPieceDefinition>,
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub struct PlacedPieceDefinition {
+    pub nomino: Nomino,
+    pub color: NominoColor,
+    #[serde(default)]
+    pub rotation: Rotation,
+    #[serde(default)]
+    pub mirrored: bool,
+    pub position: (u8, u8),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TutorialDefinition {
+    #[serde(default)]
+    pub title: Option<String>,
+    pub text: String,
+    /// Only show the tutorial when the robo-bagger is enabled.
+    #[serde(default)]
+    pub requires_robot: bool,
+    /// Animate a mouse over the first piece to demonstrate the controls.
+    #[serde(default)]
+    pub mouse_hint: bool,
+}
+
+/// Clockwise rotation, matching the direction pieces rotate in when clicked.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum Rotation {
+    #[default]
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    fn quat(self, mirrored: bool) -> Quat {
+        let rotation = match self {
+            Self::Deg0 => Quat::IDENTITY,
+            Self::Deg90 => *DEG_90,
+            Self::Deg180 => *DEG_180,
+            Self::Deg270 => DEG_90.inverse(),
+        };
+
+        if mirrored {
+            *DEG_MIRRORED * rotation
+        } else {
+            rotation
+        }
+    }
+}
+
+impl LevelDefinition {
+    /// Whether the level's pieces depend on the [`BeltSeed`].
+    #[must_use]
+    pub const fn is_seeded(&self) -> bool {
+        matches!(
+            self.belt,
+            BeltDefinition::Infinite(_) | BeltDefinition::Shuffled(_) | BeltDefinition::Fillable(_)
+        )
+    }
+
+    /// The points needed for each star, if the level declares them or its best
+    /// possible score is known.
+    #[must_use]
+    pub fn star_thresholds(&self, max_points: Option<usize>) -> Option<StarThresholds> {
+        self.stars
+            .or_else(|| max_points.map(StarThresholds::from_max_points))
+    }
+
+    /// The stars a score earns on the level. Without any thresholds, it's
+    /// rated against what its blocks would have scored tightly packed.
+    #[must_use]
+    pub fn stars(&self, score: &CurrentScore, max_points: Option<usize>) -> u8 {
+        self.star_thresholds(max_points)
+            .or_else(|| {
+                (score.packed_points > 0)
+                    .then(|| StarThresholds::from_max_points(score.packed_points))
+            })
+            .map_or(0, |thresholds| thresholds.stars(score.points))
+    }
+}
+
+impl BeltDefinition {
+    pub fn spawnable(&self, seed: BeltSeed) -> BoxedConveyorBelt {
+        match self {
+            Self::Preset(pieces) => Box::new(PresetPiecesConveyorBelt::new(
+                pieces
+                    .iter()
+                    .copied()
+                    .map(PieceDefinition::piece)
+                    .collect::<Vec<_>>(),
+            )),
+            Self::Infinite(colors) => {
+                Box::new(InfinitePiecesConveyorBelt::new(colors.clone(), *seed))
+            }
+            Self::Shuffled(colors) => {
+                Box::new(ShuffledPiecesConveyorBelt::new(colors.clone(), *seed))
+            }
+            Self::Fillable(colors) => {
+                Box::new(FillableBagsConveyorBelt::new(colors.clone(), *seed))
+            }
+        }
+    }
+}
+
+impl PieceDefinition {
+    pub fn piece(self) -> Piece {
+        Piece {
+            nomino: self.nomino,
+            color: self.color,
+            rotation: self.rotation.quat(self.mirrored),
+        }
+    }
+}
+
+impl BagDefinition {
+    pub const fn size(&self) -> BagSize {
+        BagSize::new(self.width, self.height)
+    }
+}
+
+impl PlacedPieceDefinition {
+    pub fn transform(self) -> Transform {
+        Transform::from_xyz(f32::from(self.position.0), f32::from(self.position.1), 0.)
+            .with_rotation(self.rotation.quat(self.mirrored))
+    }
+
+    /// The blocks covered by the piece in its bag's coordinates.
+    pub fn blocks(self) -> BlockPositions {
+        let position = IVec2::new(self.position.0.into(), self.position.1.into());
+        self.nomino
+            .blocks(self.rotation.quat(self.mirrored))
+            .into_iter()
+            .map(|block| position + block)
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct LevelDefinitionLoader;
+
+impl AssetLoader for LevelDefinitionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let definition = ron::de::from_bytes::<LevelDefinition>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(definition));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use gb9000_level_generation::{generate_level, Difficulty, LevelOptions};
+
+    use super::*;
+    use crate::levels::LEVELS;
+
+    #[test]
+    fn shipped_levels_parse() {
+        for path in LEVELS.iter().chain([&crate::levels::INFINITE_LEVEL]) {
+            let bytes = fs::read(format!("assets/{path}")).unwrap();
+            let definition = ron::de::from_bytes::<LevelDefinition>(&bytes)
+                .unwrap_or_else(|e| panic!("Failed to parse {path}: {e}"));
+
+            assert!(!definition.bags.is_empty(), "{path} has no bags");
+        }
+    }
+
+    #[test]
+    fn mirrored_rotation_is_applied_before_turning() {
+        let piece = PieceDefinition {
+            nomino: Nomino::TetrominoL,
+            color: NominoColor::Gold,
+            rotation: Rotation::Deg180,
+            mirrored: true,
+        };
+
+        assert_eq!(piece.piece().rotation, *DEG_MIRRORED * *DEG_180);
+    }
+
+    #[test]
+    fn shuffled_belts_are_seeded() {
+        let definition = ron::de::from_str::<LevelDefinition>(
+            "(belt: Shuffled([Gold, Gold, Pink]), bags: [(width: 3, height: 4)])",
+        )
+        .unwrap();
+
+        assert!(definition.is_seeded());
+    }
+
+    #[test]
+    fn declared_stars_override_max_score() {
+        let definition = ron::de::from_str::<LevelDefinition>(
+            "(
+                belt: Preset([]),
+                bags: [(width: 3, height: 4)],
+                stars: Some((bronze: 100, silver: 200, gold: 300)),
+            )",
+        )
+        .unwrap();
+
+        assert_eq!(definition.star_thresholds(Some(8000)).unwrap().gold, 300);
+    }
+
+    #[test]
+    fn unknown_max_score_rates_against_packed_score() {
+        let definition = ron::de::from_str::<LevelDefinition>(
+            "(belt: Infinite([Gold]), bags: [(width: 3, height: 4)])",
+        )
+        .unwrap();
+        let mut score = CurrentScore::default();
+
+        assert_eq!(definition.stars(&score, None), 0);
+
+        score.points = 6000;
+        score.packed_points = 8000;
+        assert_eq!(definition.stars(&score, None), 2);
+        assert_eq!(definition.stars(&score, Some(20000)), 0);
+    }
+
+    #[test]
+    fn generated_levels_parse() {
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            let level = generate_level(&LevelOptions {
+                bags: vec![(3, 4), (4, 4)],
+                colors: 3,
+                belt_length: None,
+                difficulty,
+                seed: 9000,
+            })
+            .unwrap();
+            let definition = ron::de::from_str::<LevelDefinition>(&level.to_ron()).unwrap();
+
+            let BeltDefinition::Preset(pieces) = definition.belt else {
+                panic!("Generated belts are preset");
+            };
+            assert_eq!(pieces.len(), level.belt.len());
+            assert_eq!(definition.bags.len(), 2);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::{
+    animations::GameSpeed,
+    bags::{BagContainerSpawner, BagGrid, BagSize},
+    conveyor_belt::{BeltSeed, ConveyorBeltSpawner},
+    gb9000::{
+        GameState::{Boot, LevelStarting, MainMenu, Playing},
+        GroceryBagger9000,
+    },
+    levels::{
+        definition::{BagDefinition, LevelDefinition},
+        tutorials::spawn_tutorial,
+        LevelFinished, LevelMarker, LevelSpawnStage, LevelStarted, LevelTransitionSystems,
+    },
+    nominos::NominoSpawner,
+    robot::{RobotOptions, RobotSpawner},
+    window_management::DipsWindow,
+};
+
+pub const LEVELS: &[&str] = &[
+    "levels/01.level.ron",
+    "levels/02.level.ron",
+    "levels/03.level.ron",
+    "levels/04.level.ron",
+    "levels/05.level.ron",
+    "levels/06.level.ron",
+    "levels/07.level.ron",
+    "levels/08.level.ron",
+    "levels/09.level.ron",
+    "levels/10.level.ron",
+    "levels/11.level.ron",
+    "levels/12.level.ron",
+    "levels/13.level.ron",
+    "levels/14.level.ron",
+    "levels/15.level.ron",
+    "levels/16.level.ron",
+    "levels/17.level.ron",
+    "levels/18.level.ron",
+    "levels/19.level.ron",
+    "levels/20.level.ron",
+    "levels/21.level.ron",
+    "levels/22.level.ron",
+    "levels/23.level.ron",
+];
+pub const INFINITE_LEVEL: &str = "levels/endless.level.ron";
+
+pub struct LevelInitPlugin;
+
+/// The rules of the level being played.
+#[derive(Default)]
+pub struct LevelRules {
+    pub mirroring: bool,
+}
+
+impl Plugin for LevelInitPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelRules>();
+
+        app.add_startup_system(load_levels);
+        app.add_system(finish_booting);
+        app.add_system_to_stage(LevelSpawnStage, init_levels);
+        app.add_system(restart_modified_level.before(LevelTransitionSystems));
+    }
+}
+
+pub struct LevelHandles {
+    levels: Vec<Handle<LevelDefinition>>,
+    infinite: Handle<LevelDefinition>,
+}
+
+impl LevelHandles {
+    #[must_use]
+    pub fn get(&self, level: u16) -> &Handle<LevelDefinition> {
+        self.levels
+            .get(usize::from(level) - 1)
+            .unwrap_or(&self.infinite)
+    }
+}
+
+fn load_levels(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelHandles {
+        levels: LEVELS.iter().map(|path| asset_server.load(*path)).collect(),
+        infinite: asset_server.load(INFINITE_LEVEL),
+    });
+}
+
+fn finish_booting(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    level_handles: Res<LevelHandles>,
+    asset_server: Res<AssetServer>,
+) {
+    if gb9000.state != Boot {
+        return;
+    }
+
+    let handles = level_handles
+        .levels
+        .iter()
+        .chain([&level_handles.infinite])
+        .map(|handle| handle.id);
+    if asset_server.get_group_load_state(handles) == LoadState::Loaded {
+        gb9000.state = MainMenu;
+    }
+}
+
+/// Restarts the current level when its definition changes on disk so level
+/// tweaks can be tried out without rebuilding. Assets are only watched with the
+/// `hot-reload` feature.
+fn restart_modified_level(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    level_handles: Res<LevelHandles>,
+    mut level_events: EventReader<AssetEvent<LevelDefinition>>,
+    mut level_finished: EventWriter<LevelFinished>,
+    level: Query<(), With<LevelMarker>>,
+) {
+    let current_level = level_handles.get(gb9000.current_level);
+    let modified = level_events
+        .iter()
+        .any(|event| matches!(event, AssetEvent::Modified { handle } if handle == current_level));
+
+    if modified && gb9000.state == Playing && !level.is_empty() {
+        gb9000.state = LevelStarting;
+        level_finished.send(LevelFinished);
+    }
+}
+
+pub fn init_levels(
+    mut commands: Commands,
+    mut level_started: EventReader<LevelStarted>,
+    dips_window: Res<DipsWindow>,
+    game_speed: Res<GameSpeed>,
+    asset_server: Res<AssetServer>,
+    robot_options: Res<RobotOptions>,
+    belt_seed: Res<BeltSeed>,
+    level_handles: Res<LevelHandles>,
+    level_definitions: Res<Assets<LevelDefinition>>,
+    mut level_rules: ResMut<LevelRules>,
+) {
+    let Some(started) = level_started.iter().last() else {
+        return;
+    };
+    let level = level_definitions
+        .get(level_handles.get(**started))
+        .expect("levels are only started after being loaded");
+    *level_rules = LevelRules {
+        mirroring: level.mirroring,
+    };
+
+    commands.spawn_belt(&dips_window, level.belt.spawnable(*belt_seed));
+
+    let sizes = level
+        .bags
+        .iter()
+        .map(BagDefinition::size)
+        .collect::<Vec<BagSize>>();
+    let bags = commands.spawn_bag(&dips_window, &game_speed, &sizes);
+    for (bag, definition) in bags.into_iter().zip(&level.bags) {
+        if definition.pieces.is_empty() {
+            continue;
+        }
+
+        let origin = Transform::from_translation(-definition.size().origin());
+        let mut grid = BagGrid::new(definition.size());
+        commands.entity(bag).with_children(|parent| {
+            for piece in &definition.pieces {
+                let transform = piece.transform();
+                let id = parent
+                    .spawn_nomino_into_bag(origin, piece.nomino, piece.color, transform)
+                    .id();
+                grid.insert(id, piece.color, &piece.blocks());
+            }
+        });
+        commands.entity(bag).insert(grid);
+    }
+
+    if level.robot {
+        commands.spawn_robot(level.robot_strategy);
+    }
+
+    if let Some(tutorial) = &level.tutorial && (!tutorial.requires_robot || robot_options.enabled) {
+        spawn_tutorial(&mut commands, &asset_server, tutorial);
+    }
+}
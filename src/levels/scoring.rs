@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
 use bevy::prelude::*;
+use smallvec::SmallVec;
 
 use crate::{
     bags::{
-        BagChangeDetectionSystems, BagChanged, BagFilled, BagMarker, BagReplacementSystems,
-        BagSize, BAG_SIZE_LARGE,
+        BagChangeDetectionSystems, BagChanged, BagFilled, BagGrid, BagMarker,
+        BagReplacementSystems, BAG_SIZE_LARGE,
     },
     levels::{LevelSpawnStage, LevelStarted},
     nominos::NominoColor,
@@ -40,7 +41,22 @@ pub struct ScoringSystems;
 pub struct CurrentScore {
     pub points: usize,
     pub all_time_points: usize,
-    score_map: HashMap<Entity, u16>,
+    /// What the placed blocks would score in tightly packed bags of a single
+    /// color.
+    pub packed_points: usize,
+    /// Each bag's score along with its packed score.
+    score_map: HashMap<Entity, (u16, u16)>,
+}
+
+impl CurrentScore {
+    /// A fresh score that carries on from the points of earlier runs.
+    #[must_use]
+    pub fn carried_over(all_time_points: usize) -> Self {
+        Self {
+            all_time_points,
+            ..default()
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -51,18 +67,29 @@ pub struct ScoreChanged {
 
 fn score_bags(
     mut bag_changes: EventReader<BagChanged>,
-    bags: Query<&BagSize, With<BagMarker>>,
+    bags: Query<&BagGrid, With<BagMarker>>,
     mut current_score: ResMut<CurrentScore>,
     mut score_changes: EventWriter<ScoreChanged>,
 ) {
-    for BagChanged { bag, blocks } in bag_changes.iter() {
-        let capacity = bags.get(*bag).unwrap().capacity();
+    for bag in bag_changes.iter() {
+        let grid = bags.get(**bag).unwrap();
+
+        let capacity = grid.size().capacity();
+        let total_bag_score = score_bag(&grid.colors(), capacity);
+        let blocks = grid.rows().flatten().filter(|cell| cell.is_some()).count();
+        let packed_bag_score =
+            max_bag_scores(&BagGrid::new(grid.size()).colors(), capacity)[blocks];
 
-        let total_bag_score = score_bag(blocks, capacity);
-        let bag_score = current_score.score_map.entry(*bag).or_insert(0);
+        let (bag_score, bag_packed_score) = current_score.score_map.entry(**bag).or_insert((0, 0));
         let diff = (i32::from(total_bag_score) - i32::from(*bag_score)) as isize;
+        let packed_diff = (i32::from(packed_bag_score) - i32::from(*bag_packed_score)) as isize;
 
         *bag_score = total_bag_score;
+        *bag_packed_score = packed_bag_score;
+        current_score.packed_points = (isize::try_from(current_score.packed_points).unwrap()
+            + packed_diff)
+            .try_into()
+            .unwrap();
         current_score.points = (isize::try_from(current_score.points).unwrap() + diff)
             .try_into()
             .unwrap();
@@ -71,7 +98,7 @@ fn score_bags(
             .try_into()
             .unwrap();
 
-        score_changes.send(ScoreChanged { cause: *bag, diff });
+        score_changes.send(ScoreChanged { cause: **bag, diff });
     }
 }
 
@@ -86,6 +113,7 @@ fn reset_score(
         }
 
         current_score.points = 0;
+        current_score.packed_points = 0;
         current_score.score_map.clear();
 
         *prev_level = **started;
@@ -93,12 +121,64 @@ fn reset_score(
 }
 
 #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-fn score_bag(bag_matrix: &[impl AsRef<[Option<NominoColor>]>], capacity: u8) -> u16 {
+pub fn score_bag(bag_matrix: &[impl AsRef<[Option<NominoColor>]>], capacity: u8) -> u16 {
     debug_assert_eq!(
         capacity as usize,
         bag_matrix.len() * bag_matrix[0].as_ref().len()
     );
 
+    let (block_count, color_block_count_map) = count_blocks(bag_matrix);
+
+    let num_holes = count_holes(bag_matrix, block_count, capacity);
+    let base_score = calculate_base_score(&color_block_count_map, capacity);
+    let multiplier = calculate_bag_fill_multiplier(block_count, capacity);
+    let hole_penalty = u16::from(num_holes) * BLOCK_POINT_VALUE;
+
+    (f32::from(multiplier) * (base_score - f32::from(hole_penalty))).round() as u16
+}
+
+/// The most a bag could score once each number of blocks, up to its free
+/// space, is put in it. Getting there requires those blocks to match its most
+/// common color without leaving any holes.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+pub fn max_bag_scores(
+    bag_matrix: &[impl AsRef<[Option<NominoColor>]>],
+    capacity: u8,
+) -> SmallVec<[u16; LARGE_BAG_CAPACITY + 1]> {
+    let (block_count, mut color_block_count_map) = count_blocks(bag_matrix);
+
+    let mut scores = SmallVec::new();
+    for added in 0..=capacity - block_count {
+        let base_score = calculate_base_score(&color_block_count_map, capacity);
+        let multiplier = calculate_bag_fill_multiplier(block_count + added, capacity);
+        scores.push((f32::from(multiplier) * base_score).round() as u16);
+
+        color_block_count_map[0] += 1;
+    }
+    scores
+}
+
+/// The most a full bag could score when `top_color_blocks` of it share its most
+/// common color. The rest of the bag is assumed to match the second most
+/// common.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+pub fn max_full_bag_score(capacity: u8, top_color_blocks: u8) -> u16 {
+    debug_assert!(top_color_blocks <= capacity);
+
+    let mut color_block_count_map = [0u8; NominoColor::COUNT];
+    color_block_count_map[0] = top_color_blocks;
+    color_block_count_map[1] = capacity - top_color_blocks;
+
+    let base_score = calculate_base_score(&color_block_count_map, capacity);
+    let multiplier = calculate_bag_fill_multiplier(capacity, capacity);
+    (f32::from(multiplier) * base_score).round() as u16
+}
+
+/// Counts the blocks in the bag along with how many there are of each color,
+/// most common first.
+fn count_blocks(
+    bag_matrix: &[impl AsRef<[Option<NominoColor>]>],
+) -> (u8, [u8; NominoColor::COUNT]) {
     let block_count = bag_matrix
         .iter()
         .flat_map(AsRef::as_ref)
@@ -113,12 +193,7 @@ fn score_bag(bag_matrix: &[impl AsRef<[Option<NominoColor>]>], capacity: u8) ->
     }
     color_block_count_map.sort_unstable_by(|a, b| b.cmp(a));
 
-    let num_holes = count_holes(bag_matrix, block_count, capacity);
-    let base_score = calculate_base_score(&color_block_count_map, capacity);
-    let multiplier = calculate_bag_fill_multiplier(block_count, capacity);
-    let hole_penalty = u16::from(num_holes) * BLOCK_POINT_VALUE;
-
-    (f32::from(multiplier) * (base_score - f32::from(hole_penalty))).round() as u16
+    (block_count, color_block_count_map)
 }
 
 fn count_holes(matrix: &[impl AsRef<[Option<NominoColor>]>], block_count: u8, capacity: u8) -> u8 {
@@ -412,6 +487,61 @@ mod tests {
         assert_eq!(6000, score_bag(&bag, 8));
     }
 
+    #[test]
+    fn max_score_assumes_matching_blocks_fill_the_bag() {
+        let bag = to_matrix(
+            6,
+            "
+            000 000
+            000 111
+            111 111
+            111 111
+            111 111
+            111 111
+        ",
+        );
+
+        let scores = max_bag_scores(&bag, 36);
+        assert_eq!(scores.len(), 10);
+        assert_eq!(2700, scores[0]);
+        assert_eq!(20000, scores[9]);
+    }
+
+    #[test]
+    fn max_score_ignores_holes() {
+        let bag = to_matrix(
+            6,
+            "
+            111 111
+            100 001
+            111 011
+            101 001
+            101 011
+            100 001
+        ",
+        );
+
+        assert!(max_bag_scores(&bag, 36)[0] > score_bag(&bag, 36));
+    }
+
+    #[test]
+    fn max_full_bag_score_matches_two_colored_bag() {
+        let bag = to_matrix(
+            6,
+            "
+            222 222
+            222 222
+            111 111
+            111 111
+            111 111
+            111 111
+        ",
+        );
+
+        assert_eq!(score_bag(&bag, 36), max_full_bag_score(36, 24));
+        assert_eq!(20000, max_full_bag_score(36, 36));
+    }
+
     fn to_matrix(cols: usize, bag: &str) -> Vec<Vec<Option<NominoColor>>> {
         let bag: String = bag.chars().filter(|c| !c.is_whitespace()).rev().collect();
         let mut matrix = Vec::new();
@@ -0,0 +1,698 @@
+use std::{
+    collections::HashMap,
+    mem,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool};
+use smallvec::SmallVec;
+
+use crate::{
+    bags::{BagColors, BagGrid, BagSize, BlockPositions, BAG_SIZE_LARGE},
+    conveyor_belt::{ConveyorBeltOptions, Piece, MAX_NUM_PIECES},
+    levels::{
+        definition::{BagDefinition, BeltDefinition, LevelDefinition},
+        scoring::{max_bag_scores, max_full_bag_score, score_bag},
+        LevelHandles, LevelSpawnStage, LevelStarted,
+    },
+    nominos::{NominoColor, NominoGeometry, DEG_90, DEG_MIRRORED},
+    robot::RobotOptions,
+};
+
+/// Caps how many states a search remembers, which keeps large levels from
+/// taking too long or running out of memory.
+const MAX_SEARCHED_STATES: usize = 50_000;
+
+pub struct SolverPlugin;
+
+impl Plugin for SolverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PossibleScore>();
+
+        app.add_system_to_stage(LevelSpawnStage, solve_level);
+    }
+}
+
+/// The best score the current level allows, worked out in the background
+/// while it's being played.
+#[derive(Debug, Default)]
+pub struct PossibleScore(Arc<Mutex<Option<MaxScore>>>);
+
+/// The best score found for a level, along with whether it's proven that no
+/// better one exists.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MaxScore {
+    pub points: usize,
+    pub proven: bool,
+}
+
+impl PossibleScore {
+    /// The level's best score, once it's been found and proven.
+    ///
+    /// # Panics
+    ///
+    /// If the solver panicked while recording a score.
+    #[must_use]
+    pub fn get(&self) -> Option<usize> {
+        self.0
+            .lock()
+            .unwrap()
+            .filter(|max| max.proven)
+            .map(|max| max.points)
+    }
+}
+
+fn solve_level(
+    mut level_started: EventReader<LevelStarted>,
+    mut possible_score: ResMut<PossibleScore>,
+    thread_pool: Res<AsyncComputeTaskPool>,
+    belt_options: Res<ConveyorBeltOptions>,
+    robot_options: Res<RobotOptions>,
+    level_handles: Res<LevelHandles>,
+    level_definitions: Res<Assets<LevelDefinition>>,
+) {
+    let Some(started) = level_started.iter().last() else {
+        return;
+    };
+    let level = level_definitions
+        .get(level_handles.get(**started))
+        .expect("levels are only started after being loaded");
+
+    // Still running searches write to the old level's score
+    *possible_score = PossibleScore::default();
+    // Background tasks run on the main thread on the web, so the search
+    // would freeze the game
+    if cfg!(target_arch = "wasm32") {
+        return;
+    }
+    // The robot takes pieces the player would need
+    if !matches!(level.belt, BeltDefinition::Preset(_)) || level.robot && robot_options.enabled {
+        return;
+    }
+
+    let level = level.clone();
+    let num_pieces_selectable = belt_options.num_pieces_selectable;
+    let result = possible_score.0.clone();
+    thread_pool
+        .spawn(async move {
+            *result.lock().unwrap() = max_score(&level, num_pieces_selectable);
+        })
+        .detach();
+}
+
+/// The best score a player could get on a level with a preset belt. `None` if
+/// the level's pieces aren't known ahead of time, or no way to place all of
+/// them was found.
+///
+/// Every order the selectable pieces could be placed in is tried, in every
+/// spot and turn the player could put them in. Robots are ignored, so levels
+/// with one may not be able to reach this score.
+#[must_use]
+pub fn max_score(level: &LevelDefinition, num_pieces_selectable: u8) -> Option<MaxScore> {
+    solve(level, num_pieces_selectable, MAX_SEARCHED_STATES)
+}
+
+fn solve(
+    level: &LevelDefinition,
+    num_pieces_selectable: u8,
+    max_states: usize,
+) -> Option<MaxScore> {
+    let BeltDefinition::Preset(pieces) = &level.belt else {
+        return None;
+    };
+
+    let bags: Bags = level
+        .bags
+        .iter()
+        .map(|definition| {
+            let mut grid = BagGrid::new(definition.size());
+            for piece in &definition.pieces {
+                grid.insert(Entity::from_raw(0), piece.color, &piece.blocks());
+            }
+            (grid, 0)
+        })
+        .collect();
+    let solver = Solver::new(
+        pieces.iter().map(|definition| definition.piece()).collect(),
+        level.bags.iter().map(BagDefinition::size).collect(),
+        level.mirroring,
+    );
+    let selectable = pieces.len().min(num_pieces_selectable.max(1).into());
+    let belt = Belt {
+        selectable: (0..selectable).collect(),
+        next: selectable,
+    };
+
+    // Tightly packed bags usually score best, so looking for those first
+    // gives the full search a high score to beat.
+    let mut packing = Search::new(None, true, max_states);
+    solver.search(&mut packing, belt.clone(), bags.clone(), 0);
+    let mut search = Search::new(packing.best, false, max_states);
+    solver.search(&mut search, belt, bags, 0);
+
+    search.best.map(|points| MaxScore {
+        points: usize::try_from(points).unwrap(),
+        proven: search.states_left > 0,
+    })
+}
+
+struct Solver {
+    pieces: Vec<SolverPiece>,
+    sizes: SmallVec<[BagSize; 3]>,
+    /// How many blocks of each color are left on the belt from each piece on.
+    blocks_from: Vec<[u16; NominoColor::COUNT]>,
+    /// The most points that could be made by filling fresh bags with a given
+    /// number of blocks.
+    fresh_bag_points: Vec<i32>,
+    same_size_bags: Option<SameSizeBags>,
+    /// Whether the bags are all the same shape, so swapping two of them
+    /// changes nothing.
+    interchangeable_bags: bool,
+}
+
+/// Bounds the score of levels whose bags all hold as many blocks by how many
+/// blocks of each color there are to fill them with.
+struct SameSizeBags {
+    capacity: u8,
+    /// The most points that could be made by spreading a given number of
+    /// blocks over bags that never fill up.
+    unfilled_points: Vec<i32>,
+}
+
+struct SolverPiece {
+    piece: Piece,
+    /// Every distinct set of blocks the player can turn the piece into.
+    orientations: SmallVec<[BlockPositions; 8]>,
+    /// The index of the first piece on the belt that's identical to this one.
+    kind: usize,
+}
+
+struct Search {
+    best: Option<i32>,
+    /// The most points any path to a state had when it was explored.
+    visited: HashMap<State, i32>,
+    /// Only tries spots covering the lowest empty block of a bag.
+    packing: bool,
+    states_left: usize,
+    /// The spots each kind of piece fits in for a bag's contents.
+    placements: HashMap<(BagColors, usize), Rc<[Placement]>>,
+}
+
+/// The pieces left on the belt and each bag's contents along with the score
+/// it was last counted at.
+#[derive(Eq, PartialEq, Hash)]
+struct State {
+    belt: Belt,
+    bags: SmallVec<[(BagColors, u16); 3]>,
+}
+
+/// The selectable pieces' indices, followed by every piece from `next` on.
+#[derive(Clone, Eq, PartialEq, Hash)]
+struct Belt {
+    selectable: SmallVec<[usize; MAX_NUM_PIECES as usize]>,
+    next: usize,
+}
+
+type Bags = SmallVec<[(BagGrid, u16); 3]>;
+
+/// The blocks a piece covers, the bag's score with it and the lowest row it
+/// reaches.
+type Placement = (BlockPositions, u16, i32);
+
+const LARGE_BAG_CAPACITY: usize = BAG_SIZE_LARGE.capacity() as usize;
+
+impl Search {
+    fn new(best: Option<i32>, packing: bool, max_states: usize) -> Self {
+        Self {
+            best,
+            visited: HashMap::new(),
+            packing,
+            states_left: max_states,
+            placements: HashMap::new(),
+        }
+    }
+}
+
+impl Solver {
+    fn new(pieces: Vec<Piece>, sizes: SmallVec<[BagSize; 3]>, mirroring: bool) -> Self {
+        let mut blocks_from = vec![[0; NominoColor::COUNT]; pieces.len() + 1];
+        for (i, piece) in pieces.iter().enumerate().rev() {
+            blocks_from[i] = blocks_from[i + 1];
+            blocks_from[i][piece.color] += u16::from(piece.nomino.block_count());
+        }
+
+        // Bags may start out with pieces in them
+        let total_blocks = usize::from(blocks_from[0].iter().sum::<u16>())
+            + sizes
+                .iter()
+                .map(|size| usize::from(size.capacity()))
+                .sum::<usize>();
+        let fresh_bag_scores = sizes
+            .iter()
+            .map(|size| max_bag_scores(&BagGrid::new(*size).colors(), size.capacity()))
+            .collect::<SmallVec<[_; 3]>>();
+        let fresh_bag_points = best_split(total_blocks, &fresh_bag_scores);
+
+        let same_size_bags = sizes
+            .iter()
+            .all(|size| size.capacity() == sizes[0].capacity())
+            .then(|| {
+                let mut scores = fresh_bag_scores[0].clone();
+                scores.pop();
+                SameSizeBags {
+                    capacity: sizes[0].capacity(),
+                    unfilled_points: best_split(total_blocks, &[scores]),
+                }
+            });
+
+        let mut solver_pieces = Vec::<SolverPiece>::with_capacity(pieces.len());
+        for piece in pieces {
+            let mut piece = SolverPiece::new(piece, solver_pieces.len(), mirroring);
+            if let Some(same) = solver_pieces.iter().find(|other| other.same_as(&piece)) {
+                piece.kind = same.kind;
+            }
+            solver_pieces.push(piece);
+        }
+
+        Self {
+            pieces: solver_pieces,
+            interchangeable_bags: sizes.iter().all(|size| *size == sizes[0]),
+            sizes,
+            blocks_from,
+            fresh_bag_points,
+            same_size_bags,
+        }
+    }
+
+    fn search(&self, search: &mut Search, belt: Belt, bags: Bags, points: i32) {
+        if search.states_left == 0 {
+            return;
+        }
+        if belt.selectable.is_empty() {
+            search.best = Some(search.best.map_or(points, |best| best.max(points)));
+            return;
+        }
+        if let Some(best) = search.best && points + self.bound(&belt, &bags) <= best {
+            return;
+        }
+
+        let contents = bags
+            .iter()
+            .map(|(grid, scored)| (grid.colors(), *scored))
+            .collect::<SmallVec<[_; 3]>>();
+        let mut state = State {
+            belt,
+            bags: contents.clone(),
+        };
+        if self.interchangeable_bags {
+            state.bags.sort_unstable();
+        }
+        if let Some(&visited) = search.visited.get(&state) && visited >= points {
+            return;
+        }
+        let belt = state.belt.clone();
+        if search.visited.insert(state, points).is_none() {
+            search.states_left -= 1;
+        }
+
+        let mut moves = Vec::new();
+        for (slot, &index) in belt.selectable.iter().enumerate() {
+            let piece = &self.pieces[index];
+            // Identical pieces leave the same choices behind
+            if belt.selectable[..slot]
+                .iter()
+                .any(|&other| self.pieces[other].kind == piece.kind)
+            {
+                continue;
+            }
+
+            for (i, (grid, scored)) in bags.iter().enumerate() {
+                if self.interchangeable_bags && contents[..i].contains(&contents[i]) {
+                    continue;
+                }
+
+                let width = i32::from(grid.size().width());
+                let lowest_empty = grid.rows().flatten().position(Option::is_none).map(|i| {
+                    let i = i32::try_from(i).unwrap();
+                    IVec2::new(i % width, i / width)
+                });
+                let placements = search
+                    .placements
+                    .entry((contents[i].0.clone(), piece.kind))
+                    .or_insert_with(|| placements(grid, piece).into())
+                    .clone();
+                for (blocks, score, row) in placements.iter() {
+                    if search.packing && let Some(lowest_empty) = lowest_empty
+                        && !blocks.contains(&lowest_empty) {
+                        continue;
+                    }
+
+                    let gain = i32::from(*score) - i32::from(*scored);
+                    moves.push((slot, i, blocks.clone(), *score, gain, *row));
+                }
+            }
+        }
+        // Trying the best looking moves first finds good scores sooner,
+        // which lets more of the search be skipped.
+        moves.sort_by_key(|&(.., gain, row)| (-gain, row));
+
+        for (slot, i, blocks, score, gain, _) in moves {
+            let mut remaining = belt.clone();
+            remaining.selectable.remove(slot);
+            if remaining.next < self.pieces.len() {
+                remaining.selectable.push(remaining.next);
+                remaining.next += 1;
+            }
+
+            let mut bags = bags.clone();
+            let mut placed = bags[i].0.clone();
+            let color = self.pieces[belt.selectable[slot]].piece.color;
+            placed.insert(Entity::from_raw(0), color, &blocks);
+            bags[i] = if placed.is_filled() {
+                (BagGrid::new(self.sizes[i]), 0)
+            } else {
+                (placed, score)
+            };
+
+            self.search(search, remaining, bags, points + gain);
+        }
+    }
+
+    /// An upper bound on the points left to gain.
+    fn bound(&self, belt: &Belt, bags: &Bags) -> i32 {
+        let mut blocks = self.blocks_from[belt.next];
+        for &i in &belt.selectable {
+            let piece = &self.pieces[i].piece;
+            blocks[piece.color] += u16::from(piece.nomino.block_count());
+        }
+
+        let bound = self.split_bound(blocks.iter().sum::<u16>().into(), bags);
+        self.same_size_bags
+            .as_ref()
+            .map_or(bound, |same_size| bound.min(same_size.bound(blocks, bags)))
+    }
+
+    /// Bounds the points left by pretending the remaining blocks can be split
+    /// between the bags however works best, regardless of their colors.
+    fn split_bound(&self, blocks: usize, bags: &Bags) -> i32 {
+        // The most points for each number of blocks used up so far, split by
+        // whether any bag could have been swapped out for a fresh one.
+        let mut best = vec![[None::<i32>; 2]; blocks + 1];
+        best[0][0] = Some(0);
+        for (grid, scored) in bags {
+            let size = grid.size();
+            let colors = grid.colors();
+            let filled = grid.rows().flatten().filter(|cell| cell.is_some()).count();
+            let room = usize::from(size.capacity()) - filled - unfillable_blocks(grid);
+
+            let gains = max_bag_scores(&colors, size.capacity())
+                .into_iter()
+                .take(room.min(blocks) + 1)
+                .map(|max| i32::from(max) - i32::from(*scored))
+                .collect::<SmallVec<[_; LARGE_BAG_CAPACITY + 1]>>();
+
+            let mut next_best = vec![[None::<i32>; 2]; blocks + 1];
+            for used in 0..=blocks {
+                for (replaced, points) in best[used].into_iter().enumerate() {
+                    let Some(points) = points else {
+                        continue;
+                    };
+
+                    for (added, gain) in gains.iter().enumerate().take(blocks - used + 1) {
+                        // A bag needs a block in every row before anything
+                        // can stick out of its top.
+                        let replaceable = filled + added >= usize::from(size.height());
+                        let entry =
+                            &mut next_best[used + added][usize::from(replaced == 1 || replaceable)];
+                        *entry = Some(entry.map_or(points + gain, |e| e.max(points + gain)));
+                    }
+                }
+            }
+            best = next_best;
+        }
+
+        best.into_iter()
+            .enumerate()
+            .flat_map(|(used, points)| {
+                let fresh = self.fresh_bag_points[blocks - used];
+                [points[0], points[1].map(|points| points + fresh)]
+            })
+            .flatten()
+            .max()
+            .unwrap()
+    }
+}
+
+impl SameSizeBags {
+    /// Bounds the points left by the blocks of each color still around, on
+    /// the belt or in the bags. Every bag that gets filled can at best be made
+    /// of the most common color left and one other.
+    fn bound(&self, mut blocks: [u16; NominoColor::COUNT], bags: &Bags) -> i32 {
+        let mut scored = 0;
+        for (grid, points) in bags {
+            for cell in grid.rows().flatten().flatten() {
+                blocks[cell.color] += 1;
+            }
+            scored += i32::from(*points);
+        }
+
+        let total = blocks.iter().sum::<u16>();
+        let capacity = u16::from(self.capacity);
+        let best = (0..=total / capacity)
+            .map(|filled_bags| {
+                let mut left = blocks;
+                let mut points = self.unfilled_points[usize::from(total - filled_bags * capacity)];
+                for _ in 0..filled_bags {
+                    let most_common = left.iter_mut().max().unwrap();
+                    let used = (*most_common).min(capacity);
+                    *most_common -= used;
+                    points += i32::from(max_full_bag_score(
+                        self.capacity,
+                        u8::try_from(used).unwrap(),
+                    ));
+                }
+                points
+            })
+            .max()
+            .unwrap();
+        best - scored
+    }
+}
+
+impl SolverPiece {
+    fn new(piece: Piece, kind: usize, mirroring: bool) -> Self {
+        let num_flips = if mirroring { 2 } else { 1 };
+
+        let mut orientations = SmallVec::<[BlockPositions; 8]>::new();
+        for flip in [Quat::IDENTITY, *DEG_MIRRORED].into_iter().take(num_flips) {
+            let mut rotation = flip * piece.rotation;
+            for _ in 0..4 {
+                let mut blocks = piece.nomino.blocks(rotation);
+                let min = blocks.iter().copied().reduce(IVec2::min).unwrap();
+                for block in &mut blocks {
+                    *block -= min;
+                }
+                blocks.sort_unstable_by_key(|block| (block.x, block.y));
+
+                if !orientations.contains(&blocks) {
+                    orientations.push(blocks);
+                }
+                rotation *= *DEG_90;
+            }
+        }
+
+        Self {
+            piece,
+            orientations,
+            kind,
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        self.piece.nomino == other.piece.nomino
+            && self.piece.color == other.piece.color
+            && self.orientations == other.orientations
+    }
+}
+
+/// The most points that could be made by spreading each number of blocks up to
+/// `blocks` over fresh bags, given the most each bag can score per block count.
+fn best_split(blocks: usize, bag_scores: &[SmallVec<[u16; LARGE_BAG_CAPACITY + 1]>]) -> Vec<i32> {
+    let mut points = vec![0; blocks + 1];
+    for total in 1..=blocks {
+        for scores in bag_scores {
+            for (filled, score) in scores.iter().enumerate().take(total + 1).skip(1) {
+                points[total] = points[total].max(i32::from(*score) + points[total - filled]);
+            }
+        }
+    }
+    points
+}
+
+/// Counts the empty blocks that no combination of pieces could ever fill: those
+/// walled off from the top of the bag in pockets too small for any piece.
+fn unfillable_blocks(grid: &BagGrid) -> usize {
+    let size = grid.size();
+    let (width, height) = (i32::from(size.width()), i32::from(size.height()));
+    let index = |block: IVec2| usize::try_from(block.y * width + block.x).unwrap();
+
+    let mut seen = [false; LARGE_BAG_CAPACITY];
+    let mut unfillable = 0;
+    for row in 0..height {
+        for col in 0..width {
+            let start = IVec2::new(col, row);
+            if grid.get(start).is_some() || mem::replace(&mut seen[index(start)], true) {
+                continue;
+            }
+
+            let mut pocket = 0;
+            let mut open = false;
+            let mut frontier = SmallVec::<[IVec2; 8]>::from_elem(start, 1);
+            while let Some(block) = frontier.pop() {
+                pocket += 1;
+                open |= block.y == height - 1;
+
+                for neighbor in [IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y].map(|d| block + d) {
+                    let inside =
+                        (0..width).contains(&neighbor.x) && (0..height).contains(&neighbor.y);
+                    if inside
+                        && grid.get(neighbor).is_none()
+                        && !mem::replace(&mut seen[index(neighbor)], true)
+                    {
+                        frontier.push(neighbor);
+                    }
+                }
+            }
+
+            // Pieces have three or four blocks
+            if !open {
+                unfillable += match pocket {
+                    1 | 2 => pocket,
+                    5 => 1,
+                    _ => 0,
+                };
+            }
+        }
+    }
+    unfillable
+}
+
+/// Every spot the piece could be placed in.
+fn placements(grid: &BagGrid, piece: &SolverPiece) -> Vec<Placement> {
+    let size = grid.size();
+    let mut placements = Vec::new();
+    for shape in &piece.orientations {
+        let width = shape.iter().map(|block| block.x).max().unwrap() + 1;
+        for row in 0..i32::from(size.height()) {
+            for col in 0..=i32::from(size.width()) - width {
+                let blocks = shape
+                    .iter()
+                    .map(|block| *block + IVec2::new(col, row))
+                    .collect::<BlockPositions>();
+                if grid.overlaps(&blocks) || !grid.supports(&blocks) {
+                    continue;
+                }
+
+                let mut placed = grid.clone();
+                placed.insert(Entity::from_raw(0), piece.piece.color, &blocks);
+                let points = score_bag(&placed.colors(), size.capacity());
+                placements.push((blocks, points, row));
+            }
+        }
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{levels::LEVELS, nominos::Nomino};
+
+    #[test]
+    fn shipped_levels_place_every_piece_within_a_short_search() {
+        // Searching up to the real limit takes minutes per level
+        for path in LEVELS.iter() {
+            let bytes = fs::read(format!("assets/{path}")).unwrap();
+            let level = ron::de::from_bytes::<LevelDefinition>(&bytes).unwrap();
+
+            assert!(
+                solve(&level, 3, 100).is_some(),
+                "{path}'s pieces can't all be placed"
+            );
+        }
+    }
+
+    #[test]
+    fn searches_stop_at_the_state_limit() {
+        let level = parse(
+            &[
+                "(nomino: TrominoL, color: Gold)",
+                "(nomino: TrominoL, color: Pink)",
+            ]
+            .repeat(6)
+            .join(","),
+        );
+
+        assert_eq!(solve(&level, 3, 100).map(|max| max.proven), Some(false));
+        assert_eq!(
+            solve(&level, 3, MAX_SEARCHED_STATES).map(|max| max.proven),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn matching_pieces_fill_the_bag() {
+        let level = parse(
+            "(nomino: TetrominoStraight, color: Gold), (nomino: TetrominoStraight, color: Gold)",
+        );
+
+        assert_eq!(
+            max_score(&level, 3),
+            Some(MaxScore {
+                points: 6000,
+                proven: true
+            })
+        );
+    }
+
+    #[test]
+    fn mixed_colors_score_less() {
+        let level = parse(
+            "(nomino: TetrominoStraight, color: Gold), (nomino: TetrominoStraight, color: Pink)",
+        );
+
+        assert_eq!(max_score(&level, 3).unwrap().points, 3500);
+    }
+
+    #[test]
+    fn mirroring_adds_flipped_orientations() {
+        let piece = Piece {
+            nomino: Nomino::TetrominoL,
+            color: NominoColor::Pink,
+            rotation: Quat::IDENTITY,
+        };
+
+        assert_eq!(SolverPiece::new(piece, 0, false).orientations.len(), 4);
+        assert_eq!(SolverPiece::new(piece, 0, true).orientations.len(), 8);
+    }
+
+    #[test]
+    fn seeded_levels_have_no_max_score() {
+        let level = ron::de::from_str::<LevelDefinition>(
+            "(belt: Shuffled([Gold]), bags: [(width: 4, height: 2)])",
+        )
+        .unwrap();
+
+        assert_eq!(max_score(&level, 3), None);
+    }
+
+    fn parse(pieces: &str) -> LevelDefinition {
+        ron::de::from_str(&format!(
+            "(belt: Preset([{pieces}]), bags: [(width: 4, height: 2)])"
+        ))
+        .unwrap()
+    }
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+/// The points needed to earn each star on a level.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub struct StarThresholds {
+    pub bronze: usize,
+    pub silver: usize,
+    pub gold: usize,
+}
+
+impl StarThresholds {
+    /// Thresholds for a level whose best possible score is known.
+    #[must_use]
+    pub const fn from_max_points(points: usize) -> Self {
+        Self {
+            bronze: points / 2,
+            silver: points * 3 / 4,
+            gold: points * 9 / 10,
+        }
+    }
+
+    /// How many stars a score earns, from none to three.
+    #[must_use]
+    pub fn stars(&self, points: usize) -> u8 {
+        [self.bronze, self.silver, self.gold]
+            .into_iter()
+            .map(|threshold| u8::from(points >= threshold))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_are_earned_at_each_threshold() {
+        let thresholds = StarThresholds::from_max_points(8000);
+
+        assert_eq!(thresholds.stars(0), 0);
+        assert_eq!(thresholds.stars(3999), 0);
+        assert_eq!(thresholds.stars(4000), 1);
+        assert_eq!(thresholds.stars(6000), 2);
+        assert_eq!(thresholds.stars(7200), 3);
+        assert_eq!(thresholds.stars(8000), 3);
+    }
+}
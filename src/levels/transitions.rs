@@ -3,11 +3,13 @@ use bevy_tweening::{AnimationSystem, TweenCompleted};
 
 use crate::{
     animations::AnimationEvent,
-    conveyor_belt::BeltEmptyEvent,
+    conveyor_belt::{BeltPieceIds, ConveyorBeltMarker},
     gb9000::{
-        GameState::{LevelEnded, Playing},
+        GameState::{GameComplete, LevelEnded, LevelStarting, Playing},
         GroceryBagger9000,
     },
+    levels::{CurrentScore, LevelDefinition, LevelHandles, PossibleScore, ScoringSystems, LEVELS},
+    run_criteria::{run_if_playing, run_on_enter},
 };
 
 pub struct LevelTransitionPlugin;
@@ -22,13 +24,16 @@ impl Plugin for LevelTransitionPlugin {
 
         app.add_event::<LevelStarted>();
         app.add_event::<LevelFinished>();
+        app.init_resource::<LevelResult>();
 
-        app.add_system(level_start_handler.label(LevelTransitionSystems));
+        // Levels start at the end of the frame so every stage gets to see the
+        // game leave LevelStarting
+        app.add_system_to_stage(CoreStage::Last, level_start_handler);
         app.add_system(
             level_end_handler
                 .label(LevelTransitionSystems)
-                .after(AnimationSystem::AnimationUpdate)
-                .before(level_start_handler),
+                .with_run_criteria(run_if_playing)
+                .after(AnimationSystem::AnimationUpdate),
         );
         app.add_system(
             level_unload_handler
@@ -36,6 +41,12 @@ impl Plugin for LevelTransitionPlugin {
                 .with_run_criteria(run_if_level_finished)
                 .after(level_end_handler),
         );
+        app.add_system(
+            record_level_result
+                .label(LevelTransitionSystems)
+                .with_run_criteria(run_on_enter(&[LevelEnded, GameComplete]))
+                .after(ScoringSystems),
+        );
     }
 }
 
@@ -54,36 +65,39 @@ pub struct LevelStarted(u16);
 
 pub struct LevelFinished;
 
+/// How the last level to end went, worked out once so the level end screen
+/// and the save agree even if the best possible score shows up later.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct LevelResult {
+    pub possible_points: Option<usize>,
+    /// `None` if the level's definition isn't loaded.
+    pub stars: Option<u8>,
+}
+
 fn level_start_handler(
-    gb9000: Res<GroceryBagger9000>,
+    mut gb9000: ResMut<GroceryBagger9000>,
     mut level_started: EventWriter<LevelStarted>,
     level: Query<(), With<LevelMarker>>,
+    level_handles: Option<Res<LevelHandles>>,
+    level_definitions: Res<Assets<LevelDefinition>>,
 ) {
-    if gb9000.state == Playing && level.is_empty() {
+    let Some(level_handles) = level_handles else {
+        return;
+    };
+    let level_loaded = level_definitions.contains(level_handles.get(gb9000.current_level));
+
+    if gb9000.state == LevelStarting && level.is_empty() && level_loaded {
+        gb9000.state = Playing;
         level_started.send(LevelStarted(gb9000.current_level));
     }
 }
 
-#[derive(Debug, Default, Eq, PartialEq)]
-pub enum LevelChangeFsm {
-    #[default]
-    Ready,
-    PiecePlaced,
-}
-
 fn level_end_handler(
     mut gb9000: ResMut<GroceryBagger9000>,
-    mut belt_empty_events: EventReader<BeltEmptyEvent>,
     mut completed_animations: EventReader<TweenCompleted>,
-    mut level_started: EventReader<LevelStarted>,
     mut level_finished: EventWriter<LevelFinished>,
-    mut level_fsm: Local<LevelChangeFsm>,
+    belt: Query<&BeltPieceIds, With<ConveyorBeltMarker>>,
 ) {
-    if level_started.iter().count() > 0 {
-        *level_fsm = LevelChangeFsm::Ready;
-    }
-
-    let belt_empty = belt_empty_events.iter().count() > 0;
     let bag_despawned = completed_animations
         .iter()
         .filter(|t| {
@@ -92,20 +106,16 @@ fn level_end_handler(
         })
         .count()
         > 0;
-
-    match *level_fsm {
-        LevelChangeFsm::Ready => {
-            if belt_empty {
-                *level_fsm = LevelChangeFsm::PiecePlaced;
-            }
-        }
-        LevelChangeFsm::PiecePlaced => {
-            if bag_despawned {
-                gb9000.state = LevelEnded;
-                level_finished.send(LevelFinished);
-                *level_fsm = default();
-            }
-        }
+    let belt_empty = belt.get_single().map_or(false, |pieces| pieces.is_empty());
+
+    // The last bags leave once every piece has been taken off the belt
+    if bag_despawned && belt_empty {
+        gb9000.state = if usize::from(gb9000.current_level) == LEVELS.len() {
+            GameComplete
+        } else {
+            LevelEnded
+        };
+        level_finished.send(LevelFinished);
     }
 }
 
@@ -117,6 +127,23 @@ fn run_if_level_finished(mut level_finished: EventReader<LevelFinished>) -> Shou
     }
 }
 
+fn record_level_result(
+    mut result: ResMut<LevelResult>,
+    gb9000: Res<GroceryBagger9000>,
+    score: Res<CurrentScore>,
+    possible_score: Res<PossibleScore>,
+    level_handles: Res<LevelHandles>,
+    level_definitions: Res<Assets<LevelDefinition>>,
+) {
+    let possible_points = possible_score.get();
+    *result = LevelResult {
+        possible_points,
+        stars: level_definitions
+            .get(level_handles.get(gb9000.current_level))
+            .map(|level| level.stars(&score, possible_points)),
+    };
+}
+
 fn level_unload_handler(mut commands: Commands, level: Query<Entity, With<LevelMarker>>) {
     for entity in level.iter() {
         commands.entity(entity).despawn_recursive();
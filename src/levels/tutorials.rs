@@ -1,11 +1,33 @@
-use bevy::{prelude::*, ui::PositionType::Absolute};
+use bevy::{asset::LoadState, math::const_vec3, prelude::*, ui::PositionType::Absolute};
+use bevy_svg::prelude::{Svg, Svg2dBundle};
+use bevy_tweening::Animator;
 
-use crate::{hex_color, levels::LevelMarker, ui::PRIMARY_FONT};
+use crate::{
+    actions::{Action, Actions},
+    animations,
+    animations::GameSpeed,
+    gb9000::{GameState::Playing, GroceryBagger9000},
+    hex_color,
+    levels::{
+        definition::TutorialDefinition, init::init_levels, LevelMarker, LevelSpawnStage,
+        LevelStarted,
+    },
+    nominos::{NominoMarker, PiecePickedUp, Selectable, DEG_180, DEG_90, DEG_MIRRORED},
+    ui::PRIMARY_FONT,
+};
 
 pub const TUTORIAL_FONT_SIZE_LARGE: f32 = 32.;
 pub const TUTORIAL_FONT_SIZE_SMALL: f32 = 24.;
 pub const TUTORIAL_FONT_COLOR: Color = hex_color!(138, 161, 238);
 
+pub struct TutorialsPlugin;
+
+impl Plugin for TutorialsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(LevelSpawnStage, show_mouse_tutorial.after(init_levels));
+    }
+}
+
 // TODO get rid of closure after https://github.com/rust-lang/rust/issues/67792
 pub const TUTORIAL_STYLE: fn() -> Style = || Style {
     size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
@@ -21,31 +43,175 @@ pub const TUTORIAL_STYLE: fn() -> Style = || Style {
     ..default()
 };
 
-pub fn spawn_text_tutorial(
+#[derive(Component)]
+struct MouseHintMarker;
+
+pub fn spawn_tutorial(
     commands: &mut Commands,
-    asset_server: Res<AssetServer>,
-    text: impl Into<String>,
+    asset_server: &AssetServer,
+    tutorial: &TutorialDefinition,
 ) {
     let font = asset_server.load(PRIMARY_FONT);
-    commands
-        .spawn_bundle(NodeBundle {
-            style: TUTORIAL_STYLE(),
-            color: Color::NONE.into(),
-            ..default()
-        })
-        .insert(LevelMarker)
-        .with_children(|parent| {
+    let mut node = commands.spawn_bundle(NodeBundle {
+        style: TUTORIAL_STYLE(),
+        color: Color::NONE.into(),
+        ..default()
+    });
+    node.insert(LevelMarker);
+    if tutorial.mouse_hint {
+        node.insert(MouseHintMarker);
+    }
+
+    node.with_children(|parent| {
+        if let Some(title) = &tutorial.title {
             parent.spawn_bundle(TextBundle {
                 text: Text::with_section(
-                    text,
+                    title,
                     TextStyle {
-                        font,
-                        font_size: TUTORIAL_FONT_SIZE_SMALL,
+                        font: font.clone(),
+                        font_size: TUTORIAL_FONT_SIZE_LARGE,
                         color: TUTORIAL_FONT_COLOR,
                     },
                     default(),
                 ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                },
                 ..default()
             });
+        }
+        parent.spawn_bundle(TextBundle {
+            text: Text::with_section(
+                &tutorial.text,
+                TextStyle {
+                    font,
+                    font_size: TUTORIAL_FONT_SIZE_SMALL,
+                    color: TUTORIAL_FONT_COLOR,
+                },
+                default(),
+            ),
+            ..default()
         });
+    });
+}
+
+#[derive(Default)]
+enum TutorialFsm {
+    #[default]
+    Ready,
+    StartedLoad,
+    Loading(Handle<Svg>, Entity, Transform),
+    Loaded(Entity, Transform),
+    PickedUp(Entity, Transform),
+    Rotated,
+}
+
+#[derive(Component)]
+struct TutorialIconMarker;
+
+fn show_mouse_tutorial(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    gb9000: Res<GroceryBagger9000>,
+    game_speed: Res<GameSpeed>,
+    actions: Res<Actions>,
+    mut level_started: EventReader<LevelStarted>,
+    mut piece_selections: EventReader<PiecePickedUp>,
+    mut fsm: Local<TutorialFsm>,
+    first_piece: Query<
+        Entity,
+        (
+            With<Selectable>,
+            With<NominoMarker>,
+            Without<Animator<Transform>>,
+        ),
+    >,
+    mouse_hint: Query<(), With<MouseHintMarker>>,
+) {
+    const ICON_SCALE: Vec3 = const_vec3!([0.05, 0.05, 0.05]);
+
+    if mouse_hint.is_empty() || level_started.iter().count() > 0 {
+        *fsm = TutorialFsm::Ready;
+        return;
+    }
+    // Picks up where it left off after a pause
+    if gb9000.state != Playing {
+        return;
+    }
+
+    match &*fsm {
+        TutorialFsm::Ready => {
+            // TODO https://github.com/Weasy666/bevy_svg/issues/10
+            *fsm = TutorialFsm::StartedLoad;
+        }
+        TutorialFsm::StartedLoad => {
+            if let Ok(piece) = first_piece.get_single() {
+                let handle = asset_server.load("icons/mouse-click.svg");
+                commands.entity(piece).with_children(|parent| {
+                    let transform = Transform::from_translation(const_vec3!([-1.25, -5., 0.]))
+                        .with_scale(Vec3::ZERO)
+                        .with_rotation(DEG_90.inverse());
+
+                    let icon = parent
+                        .spawn_bundle(Svg2dBundle {
+                            svg: handle.clone(),
+                            transform,
+                            ..default()
+                        })
+                        .insert(TutorialIconMarker)
+                        .id();
+
+                    *fsm = TutorialFsm::Loading(handle, icon, transform);
+                });
+            }
+        }
+        TutorialFsm::Loading(handle, icon, from) => {
+            if asset_server.get_load_state(handle) == LoadState::Loaded {
+                let transform = from.with_scale(ICON_SCALE);
+                commands
+                    .entity(*icon)
+                    .insert(animations::mouse_tutorial_enter(transform, &game_speed));
+
+                *fsm = TutorialFsm::Loaded(*icon, transform);
+            }
+        }
+        TutorialFsm::Loaded(icon, from) => {
+            if piece_selections.iter().count() > 0 {
+                let transform = Transform::from_translation(const_vec3!([-1.25, -2.75, 0.]))
+                    .with_scale(ICON_SCALE)
+                    .with_rotation(DEG_90.inverse() * *DEG_MIRRORED);
+                commands
+                    .entity(*icon)
+                    .insert(animations::mouse_tutorial_switch_rotation(
+                        *from,
+                        transform,
+                        &game_speed,
+                        true,
+                    ));
+
+                *fsm = TutorialFsm::PickedUp(*icon, transform);
+            }
+        }
+        TutorialFsm::PickedUp(icon, from) => {
+            if actions.just_pressed(Action::Rotate) {
+                let transform = Transform::from_translation(const_vec3!([1.25, -5., 0.]))
+                    .with_scale(ICON_SCALE)
+                    .with_rotation(*DEG_180);
+                commands
+                    .entity(*icon)
+                    .insert(animations::mouse_tutorial_switch_rotation(
+                        *from,
+                        transform,
+                        &game_speed,
+                        false,
+                    ));
+                *fsm = TutorialFsm::Rotated;
+            }
+        }
+        TutorialFsm::Rotated => {}
+    }
 }
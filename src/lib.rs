@@ -0,0 +1,34 @@
+#![feature(const_fn_floating_point_arithmetic)]
+#![feature(once_cell)]
+#![feature(option_result_contains)]
+#![feature(is_sorted)]
+#![feature(let_else)]
+#![feature(div_duration)]
+#![feature(is_some_with)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::type_complexity)]
+#![allow(clippy::needless_pass_by_value)]
+#![allow(clippy::wildcard_imports)]
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::multiple_crate_versions)]
+#![allow(clippy::option_if_let_else)]
+#![allow(clippy::forget_non_drop)] // TODO https://github.com/bevyengine/bevy/issues/4601
+
+mod actions;
+mod analytics;
+pub mod animations;
+pub mod bags;
+mod colors;
+pub mod conveyor_belt;
+#[cfg(feature = "debug")]
+pub mod debug;
+mod gamepad_utils;
+pub mod gb9000;
+pub mod levels;
+pub mod nominos;
+pub mod robot;
+mod run_criteria;
+mod save;
+mod ui;
+pub mod window_management;
+mod window_utils;
@@ -1,48 +1,20 @@
-#![feature(const_fn_floating_point_arithmetic)]
-#![feature(once_cell)]
-#![feature(option_result_contains)]
-#![feature(is_sorted)]
-#![feature(let_else)]
-#![feature(div_duration)]
-#![feature(is_some_with)]
-#![allow(clippy::too_many_arguments)]
-#![allow(clippy::type_complexity)]
-#![allow(clippy::needless_pass_by_value)]
-#![allow(clippy::wildcard_imports)]
-#![allow(clippy::module_name_repetitions)]
-#![allow(clippy::multiple_crate_versions)]
-#![allow(clippy::option_if_let_else)]
-#![allow(clippy::forget_non_drop)] // TODO https://github.com/bevyengine/bevy/issues/4601
-
 use bevy::{app::App, DefaultPlugins};
-
-use crate::{gb9000::GroceryBagger9000Plugin, window_management::WindowManager};
-
-mod analytics;
-mod animations;
-mod bags;
-mod colors;
-mod conveyor_belt;
-#[cfg(feature = "debug")]
-mod debug;
-mod gb9000;
-mod levels;
-mod nominos;
-mod robot;
-mod run_criteria;
-mod ui;
-mod window_management;
-mod window_utils;
+use grocery_bagger_9000::{gb9000::GroceryBagger9000Plugin, window_management::WindowManager};
 
 fn main() {
     let mut app = App::new();
 
     app.add_plugin(WindowManager);
+    #[cfg(feature = "hot-reload")]
+    app.insert_resource(bevy::asset::AssetServerSettings {
+        watch_for_changes: true,
+        ..Default::default()
+    });
     app.add_plugins(DefaultPlugins);
     app.add_plugin(GroceryBagger9000Plugin);
 
     #[cfg(feature = "debug")]
-    app.add_plugin(debug::DebugPlugin);
+    app.add_plugin(grocery_bagger_9000::debug::DebugPlugin);
 
     #[cfg(feature = "dump")]
     {
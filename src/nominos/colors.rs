@@ -2,10 +2,13 @@ use std::ops::{Index, IndexMut};
 
 use bevy::prelude::*;
 use num_derive::FromPrimitive;
+use serde::Deserialize;
 
 use crate::hex_color;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Component, FromPrimitive)]
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Component, FromPrimitive, Deserialize,
+)]
 pub enum NominoColor {
     Orange,
     Gold,
@@ -14,12 +17,14 @@ pub enum NominoColor {
     Pink,
     #[cfg(feature = "debug")]
     Debug,
+    #[serde(skip)]
     _Last,
 }
 
 impl NominoColor {
     pub const COUNT: usize = Self::_Last as usize;
 
+    #[must_use]
     pub fn render(self) -> Color {
         match self {
             Self::Orange => hex_color!(0xCC, 0x65, 0x2D),
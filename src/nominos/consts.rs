@@ -1,6 +1,9 @@
 use std::{f32::consts::PI, sync::LazyLock};
 
-use bevy::{math::const_vec3, prelude::*};
+use bevy::{
+    math::{const_ivec2, const_vec3},
+    prelude::*,
+};
 use bevy_prototype_lyon::prelude::{
     tess::{math::Point, path::path::Builder},
     *,
@@ -26,6 +29,12 @@ pub static TROMINO_STRAIGHT_PATH: LazyLock<Path> = LazyLock::new(|| {
 pub static TROMINO_STRAIGHT_COLLIDER: LazyLock<Collider> =
     LazyLock::new(|| Collider::cuboid(0.49, 1.49, 0.1));
 
+pub const TROMINO_STRAIGHT_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, -1]),
+    const_ivec2!([0, 0]),
+    const_ivec2!([0, 1]),
+];
+
 pub static TROMINO_L_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(6, 7);
 
@@ -55,6 +64,12 @@ pub static TROMINO_L_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
     ])
 });
 
+pub const TROMINO_L_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, 0]),
+    const_ivec2!([0, 1]),
+    const_ivec2!([1, 0]),
+];
+
 pub static TETROMINO_STRAIGHT_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(4, 5);
 
@@ -75,6 +90,13 @@ pub static TETROMINO_STRAIGHT_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
     )])
 });
 
+pub const TETROMINO_STRAIGHT_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, -2]),
+    const_ivec2!([0, -1]),
+    const_ivec2!([0, 0]),
+    const_ivec2!([0, 1]),
+];
+
 pub static TETROMINO_SQUARE_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(4, 5);
 
@@ -95,6 +117,13 @@ pub static TETROMINO_SQUARE_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
     )])
 });
 
+pub const TETROMINO_SQUARE_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, 0]),
+    const_ivec2!([1, 0]),
+    const_ivec2!([0, 1]),
+    const_ivec2!([1, 1]),
+];
+
 pub static TETROMINO_T_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(8, 9);
 
@@ -126,6 +155,13 @@ pub static TETROMINO_T_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
     ])
 });
 
+pub const TETROMINO_T_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, -1]),
+    const_ivec2!([0, 0]),
+    const_ivec2!([0, 1]),
+    const_ivec2!([1, 0]),
+];
+
 pub static TETROMINO_L_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(6, 7);
 
@@ -155,6 +191,13 @@ pub static TETROMINO_L_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
     ])
 });
 
+pub const TETROMINO_L_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, -1]),
+    const_ivec2!([0, 0]),
+    const_ivec2!([0, 1]),
+    const_ivec2!([1, -1]),
+];
+
 pub static TETROMINO_SKEW_PATH: LazyLock<Path> = LazyLock::new(|| {
     let mut b = Builder::with_capacity(8, 9);
 
@@ -183,3 +226,10 @@ pub static TETROMINO_SKEW_COLLIDER: LazyLock<Collider> = LazyLock::new(|| {
         ),
     ])
 });
+
+pub const TETROMINO_SKEW_BLOCKS: &[IVec2] = &[
+    const_ivec2!([0, -1]),
+    const_ivec2!([0, 0]),
+    const_ivec2!([1, 0]),
+    const_ivec2!([1, 1]),
+];
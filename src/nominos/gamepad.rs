@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    animations::Original,
+    bags::{BagGrid, BagMarker, BagSize, Exiting},
+    conveyor_belt::{BeltPieceIds, ConveyorBeltMarker},
+    gamepad_utils::{gamepad_just_pressed, GamepadDirection},
+    nominos::{
+        movement::{hover_over_bags, nudge, pick_up, put_back},
+        *,
+    },
+    run_criteria::run_if_playing,
+};
+
+pub struct GamepadControlPlugin;
+
+impl Plugin for GamepadControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            gamepad_piece_picker
+                .label(PieceSystems)
+                .with_run_criteria(run_if_playing),
+        );
+        app.add_system(
+            gamepad_piece_mover
+                .label(PieceSystems)
+                .with_run_criteria(run_if_playing)
+                .before(gamepad_piece_picker),
+        );
+    }
+}
+
+/// With nothing held, the shoulder buttons pick up the first or last piece,
+/// holding it over the bags. While holding a piece, they swap it for its
+/// neighbors on the belt.
+fn gamepad_piece_picker(
+    mut commands: Commands,
+    mut picked_up_events: EventWriter<PiecePickedUp>,
+    mut attempted_placement_events: EventWriter<OutOfBagPlacement>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    belt: Query<&BeltPieceIds, With<ConveyorBeltMarker>>,
+    pieces: Query<&GlobalTransform, (With<NominoMarker>, With<Selectable>)>,
+    selected: Query<(Entity, &Transform), (With<NominoMarker>, With<Selected>)>,
+    bags: Query<(&GlobalTransform, &BagSize), (With<BagMarker>, Without<Exiting>)>,
+) {
+    let pressed = |button| gamepad_just_pressed(&gamepads, &gamepad_buttons, button);
+    let step: isize = if pressed(GamepadButtonType::RightTrigger) {
+        1
+    } else if pressed(GamepadButtonType::LeftTrigger) {
+        -1
+    } else {
+        0
+    };
+    if step == 0 {
+        return;
+    }
+    let held = selected.get_single().ok();
+    let Ok(belt) = belt.get_single() else {
+        return;
+    };
+
+    let num_selectable = belt.iter().take_while(|p| pieces.contains(**p)).count();
+    if num_selectable == 0 {
+        return;
+    }
+
+    let slot = if let Some((held, _)) = held {
+        let Some(position) = belt.iter().position(|p| *p == held) else {
+            return;
+        };
+        let num_selectable = isize::try_from(num_selectable).unwrap();
+        let slot = (isize::try_from(position).unwrap() + step).rem_euclid(num_selectable);
+        usize::try_from(slot).unwrap()
+    } else if step < 0 {
+        num_selectable - 1
+    } else {
+        0
+    };
+    let piece = belt[slot];
+
+    let mut transform = Transform::from(*pieces.get(piece).unwrap());
+    if let Some((held, held_transform)) = held {
+        if held == piece {
+            return;
+        }
+
+        transform.translation = held_transform
+            .translation
+            .truncate()
+            .extend(transform.translation.z);
+        put_back(&mut attempted_placement_events, held);
+    } else {
+        transform = hover_over_bags(&bags, transform);
+    }
+
+    pick_up(&mut commands, &mut picked_up_events, piece, transform);
+}
+
+fn gamepad_piece_mover(
+    mut commands: Commands,
+    mut direction: Local<GamepadDirection>,
+    time: Res<Time>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut selected_piece: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Nomino,
+            Option<&Original<Transform>>,
+        ),
+        (With<NominoMarker>, With<Selected>),
+    >,
+    bags: Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
+    rapier_context: Res<RapierContext>,
+) {
+    let step = direction.step(&time, &gamepads, &gamepad_buttons, &gamepad_axes);
+    if step == IVec2::ZERO {
+        return;
+    }
+    let Ok((piece, mut transform, nomino, original)) = selected_piece.get_single_mut() else {
+        return;
+    };
+
+    nudge(
+        &mut commands,
+        &bags,
+        &rapier_context,
+        (piece, &mut transform, nomino, original),
+        step.as_vec2(),
+    );
+}
@@ -0,0 +1,63 @@
+use bevy::{math::const_vec2, prelude::*};
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    animations::Original,
+    bags::{BagGrid, BagMarker},
+    nominos::{movement::nudge, *},
+    run_criteria::run_if_playing,
+};
+
+pub struct KeyboardControlPlugin;
+
+impl Plugin for KeyboardControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            keyboard_piece_mover
+                .label(PieceSystems)
+                .with_run_criteria(run_if_playing),
+        );
+    }
+}
+
+const MOVE_KEYS: [(KeyCode, Vec2); 4] = [
+    (KeyCode::Left, const_vec2!([-1., 0.])),
+    (KeyCode::Right, Vec2::X),
+    (KeyCode::Up, Vec2::Y),
+    (KeyCode::Down, const_vec2!([0., -1.])),
+];
+
+fn keyboard_piece_mover(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut selected_piece: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Nomino,
+            Option<&Original<Transform>>,
+        ),
+        (With<NominoMarker>, With<Selected>),
+    >,
+    bags: Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
+    rapier_context: Res<RapierContext>,
+) {
+    let step = MOVE_KEYS
+        .iter()
+        .filter(|(key, _)| keyboard_input.just_pressed(*key))
+        .fold(Vec2::ZERO, |sum, (_, step)| sum + *step);
+    if step == Vec2::ZERO {
+        return;
+    }
+    let Ok((piece, mut transform, nomino, original)) = selected_piece.get_single_mut() else {
+        return;
+    };
+
+    nudge(
+        &mut commands,
+        &bags,
+        &rapier_context,
+        (piece, &mut transform, nomino, original),
+        step,
+    );
+}
@@ -4,21 +4,35 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier3d::prelude::*;
-use num_derive::FromPrimitive;
+use smallvec::SmallVec;
 
 pub use colors::NominoColor;
 use consts::*;
 pub use consts::{DEG_180, DEG_90, DEG_MIRRORED};
+use gamepad::GamepadControlPlugin;
+pub use gb9000_level_generation::Nomino;
+use keyboard::KeyboardControlPlugin;
 use movement::PieceMovementPlugin;
 pub use movement::{
-    OutOfBagPlacement, PiecePickedUp, PiecePlaced, PieceSystems, Selectable, Selected,
+    OutOfBagPlacement, PiecePickedUp, PiecePlaced, PieceSystems, RotatePiece, Selectable, Selected,
 };
+use pointer::PointerControlPlugin;
+pub use pointer::PointerGestures;
+pub use preview::PlacementPreview;
+use preview::PlacementPreviewPlugin;
 pub use spawn::{NominoBundle, NominoMarker, NominoSpawner};
+use undo::PieceUndoPlugin;
+pub use undo::{PieceUndoSystems, PlacementUndone, UndoPlacement};
 
 mod colors;
 mod consts;
+mod gamepad;
+mod keyboard;
 mod movement;
+mod pointer;
+mod preview;
 mod spawn;
+mod undo;
 
 pub const NOMINO_COLLIDER_GROUP: CollisionGroups = CollisionGroups {
     memberships: 0b1,
@@ -30,23 +44,27 @@ pub struct PiecesPlugin;
 impl Plugin for PiecesPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugin(PieceMovementPlugin);
+        app.add_plugin(PieceUndoPlugin);
+        app.add_plugin(KeyboardControlPlugin);
+        app.add_plugin(GamepadControlPlugin);
+        app.add_plugin(PointerControlPlugin);
+        app.add_plugin(PlacementPreviewPlugin);
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Component, FromPrimitive)]
-pub enum Nomino {
-    TrominoStraight,
-    TrominoL,
-    TetrominoStraight,
-    TetrominoSquare,
-    TetrominoT,
-    TetrominoL,
-    TetrominoSkew,
-    _Last,
+/// Game-side data for the shapes shared with the level generator.
+pub trait NominoGeometry {
+    fn path(self) -> &'static Path;
+
+    fn collider(self) -> &'static Collider;
+
+    /// The blocks making up this piece relative to its pivot block once
+    /// rotated, in units of blocks.
+    fn blocks(self, rotation: Quat) -> SmallVec<[IVec2; 4]>;
 }
 
-impl Nomino {
-    fn path(&self) -> &Path {
+impl NominoGeometry for Nomino {
+    fn path(self) -> &'static Path {
         match self {
             Self::TrominoStraight => &TROMINO_STRAIGHT_PATH,
             Self::TrominoL => &TROMINO_L_PATH,
@@ -55,11 +73,10 @@ impl Nomino {
             Self::TetrominoT => &TETROMINO_T_PATH,
             Self::TetrominoL => &TETROMINO_L_PATH,
             Self::TetrominoSkew => &TETROMINO_SKEW_PATH,
-            Self::_Last => panic!("you shouldn't be here!"),
         }
     }
 
-    fn collider(&self) -> &Collider {
+    fn collider(self) -> &'static Collider {
         match self {
             Self::TrominoStraight => &TROMINO_STRAIGHT_COLLIDER,
             Self::TrominoL => &TROMINO_L_COLLIDER,
@@ -68,7 +85,28 @@ impl Nomino {
             Self::TetrominoT => &TETROMINO_T_COLLIDER,
             Self::TetrominoL => &TETROMINO_L_COLLIDER,
             Self::TetrominoSkew => &TETROMINO_SKEW_COLLIDER,
-            Self::_Last => panic!("you shouldn't be here!"),
         }
     }
+
+    fn blocks(self, rotation: Quat) -> SmallVec<[IVec2; 4]> {
+        let blocks = match self {
+            Self::TrominoStraight => TROMINO_STRAIGHT_BLOCKS,
+            Self::TrominoL => TROMINO_L_BLOCKS,
+            Self::TetrominoStraight => TETROMINO_STRAIGHT_BLOCKS,
+            Self::TetrominoSquare => TETROMINO_SQUARE_BLOCKS,
+            Self::TetrominoT => TETROMINO_T_BLOCKS,
+            Self::TetrominoL => TETROMINO_L_BLOCKS,
+            Self::TetrominoSkew => TETROMINO_SKEW_BLOCKS,
+        };
+
+        blocks
+            .iter()
+            .map(|block| {
+                (rotation * block.as_vec2().extend(0.))
+                    .truncate()
+                    .round()
+                    .as_ivec2()
+            })
+            .collect()
+    }
 }
@@ -1,14 +1,18 @@
-use bevy::{ecs::schedule::ShouldRun, math::const_vec3, prelude::*};
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
 use bevy_rapier3d::prelude::*;
 use bevy_tweening::AnimationSystem;
 use smallvec::SmallVec;
 
 use crate::{
+    actions::{Action, Actions, NUM_SLOT_ACTIONS},
     animations,
     animations::{AnimationComponentsBundle, GameSpeed, Original},
-    bags::{BAG_BOUNDARY_COLLIDER_GROUP, BAG_COLLIDER_GROUP, BAG_FLOOR_COLLIDER_GROUP},
-    levels::LevelMarker,
+    bags::{BagGrid, BagMarker, BagSize, Exiting},
+    conveyor_belt::{BeltPieceIds, ConveyorBeltMarker},
+    gb9000::{GameState::Playing, GroceryBagger9000},
+    levels::{LevelMarker, LevelRules},
     nominos::*,
+    run_criteria::run_if_playing,
     window_management::{DipsWindow, MainCamera, WindowSystems},
     window_utils::compute_cursor_position,
 };
@@ -20,22 +24,37 @@ impl Plugin for PieceMovementPlugin {
         app.add_event::<PiecePickedUp>();
         app.add_event::<OutOfBagPlacement>();
         app.add_event::<PiecePlaced>();
+        app.add_event::<RotatePiece>();
 
         app.add_system(
             piece_selection_handler
                 .label(PieceSystems)
-                .with_run_criteria(run_if_left_clicked),
+                .with_run_criteria(run_if_selection_pressed),
+        );
+        app.add_system(
+            slot_piece_picker
+                .label(PieceSystems)
+                .with_run_criteria(run_if_playing),
         );
         app.add_system(
             piece_rotation_handler
                 .label(PieceSystems)
-                .with_run_criteria(run_if_right_clicked)
+                .with_run_criteria(run_if_rotation_pressed)
+                .before(piece_selection_handler)
+                .after(AnimationSystem::AnimationUpdate),
+        );
+        app.add_system(
+            piece_mirror_handler
+                .label(PieceSystems)
+                .with_run_criteria(run_if_mirror_pressed)
                 .before(piece_selection_handler)
+                .after(piece_rotation_handler)
                 .after(AnimationSystem::AnimationUpdate),
         );
         app.add_system(
             selected_piece_mover
                 .label(PieceSystems)
+                .with_run_criteria(run_if_playing)
                 .after(WindowSystems)
                 .before(piece_selection_handler)
                 .after(AnimationSystem::AnimationUpdate),
@@ -61,15 +80,22 @@ pub struct OutOfBagPlacement(Entity);
 pub struct PiecePlaced {
     pub piece: Entity,
     pub bag: Entity,
+    pub by_robot: bool,
 }
 
-const FLOATING_PIECE_COLLIDER_GROUP: CollisionGroups = CollisionGroups {
-    memberships: BAG_FLOOR_COLLIDER_GROUP.memberships | NOMINO_COLLIDER_GROUP.memberships,
-    filters: BAG_FLOOR_COLLIDER_GROUP.filters | NOMINO_COLLIDER_GROUP.filters,
-};
-
-fn run_if_left_clicked(mouse_button_input: Res<Input<MouseButton>>) -> ShouldRun {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
+/// Asks for the held piece to be rotated, such as from an on-screen button.
+pub struct RotatePiece;
+
+fn run_if_selection_pressed(
+    actions: Res<Actions>,
+    pointer_gestures: Res<PointerGestures>,
+    gb9000: Res<GroceryBagger9000>,
+) -> ShouldRun {
+    let pressed = actions.just_pressed(Action::PickUp)
+        || actions.just_pressed(Action::Place)
+        || pointer_gestures.pressed.is_some()
+        || pointer_gestures.dropped;
+    if pressed && gb9000.state == Playing {
         ShouldRun::Yes
     } else {
         ShouldRun::No
@@ -82,16 +108,19 @@ fn piece_selection_handler(
     mut placed_events: EventWriter<PiecePlaced>,
     mut attempted_placement_events: EventWriter<OutOfBagPlacement>,
     selectables: Query<&Selectable, With<NominoMarker>>,
+    actions: Res<Actions>,
+    pointer_gestures: Res<PointerGestures>,
     game_speed: Res<GameSpeed>,
     windows: Res<Windows>,
     camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
     rapier_context: Res<RapierContext>,
+    bags: Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
     mut pieces_queries: ParamSet<(
         Query<
             (
                 Entity,
                 &mut Transform,
-                &Collider,
+                &Nomino,
                 Option<&Original<Transform>>,
             ),
             (With<NominoMarker>, With<Selected>),
@@ -102,7 +131,12 @@ fn piece_selection_handler(
 ) {
     {
         let mut selected_shape = pieces_queries.p0();
-        if let Ok((piece, mut transform, collider, original)) = selected_shape.get_single_mut() {
+        if let Ok((piece, mut transform, nomino, original)) = selected_shape.get_single_mut() {
+            // Putting a finger down starts a drag, lifting it places the piece
+            if !actions.just_pressed(Action::Place) && !pointer_gestures.dropped {
+                return;
+            }
+
             if let Some(original) = original {
                 transform.rotation = original.rotation;
                 commands
@@ -116,24 +150,20 @@ fn piece_selection_handler(
                 return;
             }
 
-            let intersects_with_bag = rapier_context.intersection_with_shape(
-                transform.translation,
-                transform.rotation,
-                collider,
-                BAG_COLLIDER_GROUP.into(),
-                None,
-            );
-
-            if let Some(bag) = intersects_with_bag {
-                if !straddles_bag_or_overlaps_pieces(&rapier_context, *transform, collider, piece)
-                    && !piece_is_floating(&rapier_context, *transform, collider, piece)
-                {
+            if let Some((bag, valid)) =
+                placement_in_bag(&bags, &rapier_context, piece, *transform, *nomino)
+            {
+                if valid {
                     commands
                         .entity(piece)
                         .remove::<Selected>()
                         .insert(animations::piece_placed(*transform, &game_speed));
 
-                    placed_events.send(PiecePlaced { piece, bag });
+                    placed_events.send(PiecePlaced {
+                        piece,
+                        bag,
+                        by_robot: false,
+                    });
                 } else {
                     commands
                         .entity(piece)
@@ -147,7 +177,14 @@ fn piece_selection_handler(
         }
     }
 
-    let cursor_position = if let Some(cursor_position) = compute_cursor_position(windows, camera) {
+    // Pick up can be rebound to a key, which picks up whatever is under the cursor
+    let cursor_position = pointer_gestures.pressed.or_else(|| {
+        actions
+            .just_pressed(Action::PickUp)
+            .then(|| compute_cursor_position(windows, camera))
+            .flatten()
+    });
+    let cursor_position = if let Some(cursor_position) = cursor_position {
         cursor_position
     } else {
         return;
@@ -169,18 +206,13 @@ fn piece_selection_handler(
                 let piece_positions = pieces_queries.p1();
                 let (piece_position, ..) = piece_positions.get(id).unwrap();
 
-                picked_up_events.send(PiecePickedUp(id));
-
-                commands
-                    .entity(id)
-                    .insert(LevelMarker)
-                    .insert(Selected)
-                    .insert(
-                        piece_position
-                            .with_translation(cursor_position.extend(piece_position.translation.z)),
-                    )
-                    .remove::<Parent>()
-                    .remove_bundle::<AnimationComponentsBundle<Transform>>();
+                pick_up(
+                    &mut commands,
+                    &mut picked_up_events,
+                    id,
+                    piece_position
+                        .with_translation(cursor_position.extend(piece_position.translation.z)),
+                );
             }
             failed_selection = if selectable { None } else { Some(id) };
 
@@ -202,8 +234,83 @@ fn piece_selection_handler(
     }
 }
 
-fn run_if_right_clicked(mouse_button_input: Res<Input<MouseButton>>) -> ShouldRun {
-    if mouse_button_input.just_pressed(MouseButton::Right) {
+/// Takes the piece off the belt, leaving it at `transform` in world
+/// coordinates.
+pub fn pick_up(
+    commands: &mut Commands,
+    picked_up_events: &mut EventWriter<PiecePickedUp>,
+    piece: Entity,
+    transform: Transform,
+) {
+    picked_up_events.send(PiecePickedUp(piece));
+    commands
+        .entity(piece)
+        .insert(LevelMarker)
+        .insert(Selected)
+        .insert(transform)
+        .remove::<Parent>()
+        .remove_bundle::<AnimationComponentsBundle<Transform>>();
+}
+
+/// Moves a piece above the leftmost bag, ready to be lowered into it.
+pub fn hover_over_bags(
+    bags: &Query<(&GlobalTransform, &BagSize), (With<BagMarker>, Without<Exiting>)>,
+    mut piece: Transform,
+) -> Transform {
+    if let Some((bag_position, bag_size)) = bags
+        .iter()
+        .min_by(|a, b| a.0.translation.x.total_cmp(&b.0.translation.x))
+    {
+        let above_bag = bag_position.translation + Vec3::Y * (bag_size.half_height() + 3.);
+        piece.translation = above_bag.truncate().round().extend(piece.translation.z);
+    }
+    piece
+}
+
+/// Sends the held piece back to its spot on the belt.
+pub fn put_back(attempted_placement_events: &mut EventWriter<OutOfBagPlacement>, piece: Entity) {
+    attempted_placement_events.send(OutOfBagPlacement(piece));
+}
+
+/// Picks up the piece in the belt slot whose action was pressed and holds it
+/// over the leftmost bag.
+fn slot_piece_picker(
+    mut commands: Commands,
+    mut picked_up_events: EventWriter<PiecePickedUp>,
+    actions: Res<Actions>,
+    belt: Query<&BeltPieceIds, With<ConveyorBeltMarker>>,
+    pieces: Query<&GlobalTransform, (With<NominoMarker>, With<Selectable>)>,
+    selected: Query<(), With<Selected>>,
+    bags: Query<(&GlobalTransform, &BagSize), (With<BagMarker>, Without<Exiting>)>,
+) {
+    let Some(slot) = (1..=NUM_SLOT_ACTIONS)
+        .position(|slot| actions.just_pressed(Action::SelectSlot(slot))) else {
+        return;
+    };
+    if !selected.is_empty() {
+        return;
+    }
+    let Ok(belt) = belt.get_single() else {
+        return;
+    };
+    let Some(piece) = belt.get(slot) else {
+        return;
+    };
+    let Ok(piece_position) = pieces.get(*piece) else {
+        return;
+    };
+
+    let transform = hover_over_bags(&bags, (*piece_position).into());
+    pick_up(&mut commands, &mut picked_up_events, *piece, transform);
+}
+
+fn run_if_rotation_pressed(
+    actions: Res<Actions>,
+    mut rotation_requests: EventReader<RotatePiece>,
+    gb9000: Res<GroceryBagger9000>,
+) -> ShouldRun {
+    let pressed = actions.just_pressed(Action::Rotate) || rotation_requests.iter().count() > 0;
+    if pressed && gb9000.state == Playing {
         ShouldRun::Yes
     } else {
         ShouldRun::No
@@ -234,36 +341,77 @@ fn piece_rotation_handler(
     }
 }
 
+fn run_if_mirror_pressed(
+    actions: Res<Actions>,
+    level_rules: Res<LevelRules>,
+    gb9000: Res<GroceryBagger9000>,
+) -> ShouldRun {
+    if actions.just_pressed(Action::Mirror) && level_rules.mirroring && gb9000.state == Playing {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Flips the held piece from left to right. Mirrored pieces come back to their
+/// unmirrored rotation, so turning them afterwards goes clockwise again.
+fn piece_mirror_handler(
+    mut commands: Commands,
+    mut selected_piece: Query<
+        (Entity, &mut Transform, Option<&Original<Transform>>),
+        (With<NominoMarker>, With<Selected>),
+    >,
+) {
+    if let Ok((piece, mut transform, original)) = selected_piece.get_single_mut() {
+        if let Some(original) = original {
+            transform.rotation = original.rotation;
+            commands
+                .entity(piece)
+                .remove_bundle::<AnimationComponentsBundle<Transform>>();
+        }
+
+        let rotation = &mut transform.rotation;
+        if rotation.x.abs() > 1e-5 || rotation.y.abs() > 1e-5 {
+            *rotation = DEG_MIRRORED.inverse() * *rotation;
+        } else {
+            *rotation = *DEG_MIRRORED * *rotation;
+        }
+    }
+}
+
 fn selected_piece_mover(
     mut commands: Commands,
     dips_window: Res<DipsWindow>,
     mut cursor_movements: EventReader<CursorMoved>,
+    pointer_gestures: Res<PointerGestures>,
     mut last_snapped_cursor_position: Local<Vec2>,
     mut selected_piece: Query<
         (
             Entity,
             &GlobalTransform,
             &mut Transform,
-            &Collider,
+            &Nomino,
             Option<&Original<Transform>>,
         ),
         (With<NominoMarker>, With<Selected>),
     >,
+    bags: Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
     rapier_context: Res<RapierContext>,
 ) {
-    let moved_event = if let Some(moved_event) = cursor_movements.iter().last() {
-        moved_event
+    let cursor_position = if let Some(moved_event) = cursor_movements.iter().last() {
+        moved_event.position * dips_window.scale
+    } else if let Some(dragged) = pointer_gestures.dragged {
+        dragged
     } else {
         return;
     };
-    let (piece, global_transform, mut piece_transform, collider, original) =
+    let (piece, global_transform, mut piece_transform, nomino, original) =
         if let Ok(s) = selected_piece.get_single_mut() {
             s
         } else {
             return;
         };
 
-    let cursor_position = moved_event.position * dips_window.scale;
     let snapped_cursor_position = cursor_position.round();
 
     if *last_snapped_cursor_position == snapped_cursor_position {
@@ -286,10 +434,11 @@ fn selected_piece_mover(
     for position in nearby_positions {
         let snapped_cursor_position = position.extend(piece_transform.translation.z);
         let would_move_over_invalid_position = straddles_bag_or_overlaps_pieces(
+            &bags,
             &rapier_context,
-            Transform::from_translation(snapped_cursor_position).with_rotation(rotation),
-            collider,
             piece,
+            Transform::from_translation(snapped_cursor_position).with_rotation(rotation),
+            *nomino,
         );
         if would_move_over_invalid_position {
             continue;
@@ -307,47 +456,83 @@ fn selected_piece_mover(
     }
 }
 
-fn straddles_bag_or_overlaps_pieces(
+/// Moves the held piece by `step` blocks, refusing to push it through a bag
+/// or into other pieces.
+pub fn nudge(
+    commands: &mut Commands,
+    bags: &Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
+    rapier_context: &RapierContext,
+    (piece, transform, nomino, original): (
+        Entity,
+        &mut Transform,
+        &Nomino,
+        Option<&Original<Transform>>,
+    ),
+    step: Vec2,
+) {
+    let rotation = original.map_or(transform.rotation, |o| o.rotation);
+    let target = (transform.translation.truncate() + step)
+        .round()
+        .extend(transform.translation.z);
+    if straddles_bag_or_overlaps_pieces(
+        bags,
+        rapier_context,
+        piece,
+        Transform::from_translation(target).with_rotation(rotation),
+        *nomino,
+    ) {
+        return;
+    }
+
+    if original.is_some() {
+        transform.rotation = rotation;
+        commands
+            .entity(piece)
+            .remove_bundle::<AnimationComponentsBundle<Transform>>();
+    }
+    transform.translation = target;
+}
+
+/// The bag the piece would be placed in and whether it fits there, that is
+/// without straddling a wall, overlapping other pieces or floating.
+pub fn placement_in_bag(
+    bags: &Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
     rapier_context: &RapierContext,
+    piece: Entity,
     transform: Transform,
-    collider: &Collider,
-    self_id: Entity,
-) -> bool {
-    rapier_context
-        .intersection_with_shape(
-            transform.translation,
-            transform.rotation,
-            collider,
-            BAG_BOUNDARY_COLLIDER_GROUP.into(),
-            None,
-        )
-        .or_else(|| {
-            rapier_context.intersection_with_shape(
-                transform.translation,
-                transform.rotation,
-                collider,
-                NOMINO_COLLIDER_GROUP.into(),
-                Some(&|entity| entity != self_id),
-            )
-        })
-        .is_some()
+    nomino: Nomino,
+) -> Option<(Entity, bool)> {
+    let (bag, blocks, grid) = bags.iter().find_map(|(bag, bag_coords, grid)| {
+        let blocks = grid.piece_blocks(bag_coords.translation, nomino, transform);
+        grid.intersects(&blocks).then_some((bag, blocks, grid))
+    })?;
+
+    let valid = !straddles_bag_or_overlaps_pieces(bags, rapier_context, piece, transform, nomino)
+        && grid.supports(&blocks);
+    Some((bag, valid))
 }
 
-fn piece_is_floating(
+pub fn straddles_bag_or_overlaps_pieces(
+    bags: &Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
     rapier_context: &RapierContext,
+    piece: Entity,
     transform: Transform,
-    collider: &Collider,
-    self_id: Entity,
+    nomino: Nomino,
 ) -> bool {
-    // Check that the piece isn't floating by seeing if moving it down one unit
-    // intersects with another piece.
-    rapier_context
-        .intersection_with_shape(
-            transform.translation - const_vec3!([0., 0.5, 0.]),
-            transform.rotation,
-            collider,
-            FLOATING_PIECE_COLLIDER_GROUP.into(),
-            Some(&|entity| entity != self_id),
-        )
-        .is_none()
+    let in_bags = bags.iter().any(|(_, bag_coords, grid)| {
+        let blocks = grid.piece_blocks(bag_coords.translation, nomino, transform);
+        grid.straddles(&blocks) || grid.overlaps(&blocks)
+    });
+
+    // Pieces outside the bags, like the ones on the belt, aren't in any grid
+    in_bags
+        || rapier_context
+            .intersection_with_shape(
+                transform.translation,
+                transform.rotation,
+                nomino.collider(),
+                NOMINO_COLLIDER_GROUP.into(),
+                Some(&|entity| entity != piece),
+            )
+            .is_some()
 }
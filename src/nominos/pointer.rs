@@ -0,0 +1,155 @@
+use bevy::{
+    input::{touch::Touch, InputSystem},
+    prelude::*,
+};
+
+use crate::{
+    nominos::{NominoMarker, RotatePiece, Selected},
+    window_management::DipsWindow,
+};
+
+pub struct PointerControlPlugin;
+
+impl Plugin for PointerControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerGestures>();
+
+        app.add_system_to_stage(CoreStage::PreUpdate, track_pointer.after(InputSystem));
+    }
+}
+
+/// Pointers travelling less than this many blocks are tapping rather than
+/// dragging.
+const DRAG_THRESHOLD: f32 = 0.5;
+/// How close to the held piece's pivot a tap has to land to rotate it.
+const TAP_RADIUS: f32 = 1.5;
+
+/// What the player's pointer did this frame, in world coordinates.
+#[derive(Default)]
+pub struct PointerGestures {
+    /// The pointer went down, picking up whatever is under it.
+    pub pressed: Option<Vec2>,
+    /// The pointer is dragging the held piece around.
+    pub dragged: Option<Vec2>,
+    /// The pointer was lifted after dragging, dropping the held piece. The
+    /// desktop mouse keeps clicking to place instead.
+    pub dropped: bool,
+}
+
+/// Either the first finger on the screen or the left mouse button. Browsers
+/// report touches as the mouse, so on the web fingers are always the latter
+/// and letting go of the mouse after a drag drops the held piece there too.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Pointer {
+    Finger(u64),
+    Mouse,
+}
+
+struct TrackedPointer {
+    pointer: Pointer,
+    start: Vec2,
+    was_holding: bool,
+    dragging: bool,
+    cancelled: bool,
+}
+
+/// Follows the first pointer to go down. Tapping the held piece with a finger
+/// or tapping with a second finger rotates it.
+fn track_pointer(
+    mut gestures: ResMut<PointerGestures>,
+    mut tracked: Local<Option<TrackedPointer>>,
+    mut cursor: Local<Option<Vec2>>,
+    mut cursor_movements: EventReader<CursorMoved>,
+    mut rotations: EventWriter<RotatePiece>,
+    touches: Res<Touches>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    dips_window: Res<DipsWindow>,
+    held_piece: Query<&GlobalTransform, (With<NominoMarker>, With<Selected>)>,
+) {
+    *gestures = default();
+
+    let cursor_moved = if let Some(moved_event) = cursor_movements.iter().last() {
+        *cursor = Some(moved_event.position * dips_window.scale);
+        true
+    } else {
+        false
+    };
+
+    // Touches start from the top of the window unlike the cursor
+    let height = windows.get_primary().map_or(0., Window::height);
+    let to_world = |touch: &Touch| {
+        let position = touch.position();
+        Vec2::new(position.x, height - position.y) * dips_window.scale
+    };
+
+    let just_pressed = touches
+        .iter_just_pressed()
+        .next()
+        .map(|touch| (Pointer::Finger(touch.id()), to_world(touch)))
+        .or_else(|| {
+            cursor
+                .filter(|_| mouse_buttons.just_pressed(MouseButton::Left))
+                .map(|position| (Pointer::Mouse, position))
+        });
+    if let Some((pointer, position)) = just_pressed {
+        if let Some(tracked) = &mut *tracked {
+            if !tracked.dragging && !tracked.cancelled {
+                rotations.send(RotatePiece);
+            }
+            tracked.cancelled = true;
+        } else {
+            *tracked = Some(TrackedPointer {
+                pointer,
+                start: position,
+                was_holding: !held_piece.is_empty(),
+                dragging: false,
+                cancelled: false,
+            });
+            gestures.pressed = Some(position);
+        }
+    }
+
+    let Some(tracked_pointer) = tracked.as_mut() else {
+        return;
+    };
+
+    let (held, released) = match tracked_pointer.pointer {
+        Pointer::Finger(id) => (
+            touches
+                .get_pressed(id)
+                .map(|finger| (to_world(finger), finger.delta() != Vec2::ZERO)),
+            touches.get_released(id).map(to_world),
+        ),
+        Pointer::Mouse => (
+            cursor
+                .filter(|_| mouse_buttons.pressed(MouseButton::Left))
+                .map(|position| (position, cursor_moved)),
+            cursor.filter(|_| mouse_buttons.just_released(MouseButton::Left)),
+        ),
+    };
+
+    if let Some((position, moved)) = held {
+        tracked_pointer.dragging |=
+            !tracked_pointer.cancelled && position.distance(tracked_pointer.start) > DRAG_THRESHOLD;
+        if tracked_pointer.dragging && moved {
+            gestures.dragged = Some(position);
+        }
+        return;
+    }
+
+    if let Some(position) = released && !tracked_pointer.cancelled {
+        if tracked_pointer.dragging {
+            gestures.dropped =
+                tracked_pointer.pointer != Pointer::Mouse || cfg!(target_arch = "wasm32");
+        } else if tracked_pointer.was_holding
+            // The held piece follows the mouse, so it's always under it
+            && tracked_pointer.pointer != Pointer::Mouse
+            && let Ok(piece) = held_piece.get_single()
+            && piece.translation.truncate().distance(position) < TAP_RADIUS
+        {
+            rotations.send(RotatePiece);
+        }
+    }
+    *tracked = None;
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::*;
+use bevy_rapier3d::prelude::RapierContext;
+
+use crate::{
+    animations::Original,
+    bags::{BagGrid, BagMarker},
+    levels::LevelMarker,
+    nominos::{movement::placement_in_bag, *},
+};
+
+pub struct PlacementPreviewPlugin;
+
+impl Plugin for PlacementPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(preview_placement.after(PieceSystems));
+    }
+}
+
+const VALID_PLACEMENT_COLOR: Color = Color::rgba(1., 1., 1., 0.25);
+const INVALID_PLACEMENT_COLOR: Color = Color::rgba(0.9, 0.15, 0.15, 0.5);
+
+/// Shades the held piece while it's over a bag to show whether it can be
+/// placed there.
+#[derive(Component)]
+pub struct PlacementPreview {
+    pub piece: Entity,
+    pub valid: bool,
+}
+
+fn preview_placement(
+    mut commands: Commands,
+    held_piece: Query<
+        (Entity, &Transform, &Nomino, Option<&Original<Transform>>),
+        (With<NominoMarker>, With<Selected>),
+    >,
+    bags: Query<(Entity, &GlobalTransform, &BagGrid), With<BagMarker>>,
+    rapier_context: Res<RapierContext>,
+    mut previews: Query<
+        (Entity, &mut PlacementPreview, &mut Transform, &mut DrawMode),
+        Without<NominoMarker>,
+    >,
+) {
+    let target = held_piece
+        .get_single()
+        .ok()
+        .and_then(|(piece, transform, nomino, original)| {
+            // Placing a piece cancels its animations
+            let mut transform = *transform;
+            if let Some(original) = original {
+                transform.rotation = original.rotation;
+            }
+            placement_in_bag(&bags, &rapier_context, piece, transform, *nomino)
+                .map(|(_, valid)| (piece, transform, *nomino, valid))
+        });
+
+    let mut preview = previews.get_single_mut().ok();
+    if let Some((id, previewed, ..)) = &preview
+        && target.map_or(true, |(piece, ..)| piece != previewed.piece)
+    {
+        commands.entity(*id).despawn();
+        preview = None;
+    }
+    let Some((piece, mut transform, nomino, valid)) = target else {
+        return;
+    };
+    transform.translation.z += 0.01;
+
+    if let Some((_, mut previewed, mut preview_transform, mut draw_mode)) = preview {
+        if *preview_transform != transform {
+            *preview_transform = transform;
+        }
+        if previewed.valid != valid {
+            previewed.valid = valid;
+            *draw_mode = preview_draw_mode(valid);
+        }
+    } else {
+        commands
+            .spawn_bundle(GeometryBuilder::build_as(
+                nomino.path(),
+                preview_draw_mode(valid),
+                transform,
+            ))
+            .insert(PlacementPreview { piece, valid })
+            .insert(LevelMarker);
+    }
+}
+
+fn preview_draw_mode(valid: bool) -> DrawMode {
+    DrawMode::Fill(FillMode::color(if valid {
+        VALID_PLACEMENT_COLOR
+    } else {
+        INVALID_PLACEMENT_COLOR
+    }))
+}
@@ -5,7 +5,7 @@ use bevy_prototype_lyon::{
 };
 use bevy_rapier3d::prelude::*;
 
-use crate::nominos::{Nomino, NominoColor, NOMINO_COLLIDER_GROUP};
+use crate::nominos::{Nomino, NominoColor, NominoGeometry, NOMINO_COLLIDER_GROUP};
 
 #[derive(Default, Component)]
 pub struct NominoMarker;
@@ -38,6 +38,7 @@ pub struct NominoBundle {
 }
 
 impl NominoBundle {
+    #[must_use]
     pub fn new(
         position: Transform,
         nomino: Nomino,
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use smallvec::SmallVec;
+
+use crate::{
+    actions::{Action, Actions},
+    bags::{BagMarker, Exiting},
+    conveyor_belt::{BeltPieceIds, ConveyorBeltMarker},
+    gb9000::{GameState::Playing, GroceryBagger9000},
+    levels::LevelStarted,
+    nominos::{PiecePlaced, PieceSystems},
+};
+
+pub struct PieceUndoPlugin;
+
+impl Plugin for PieceUndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UndoPlacement>();
+        app.add_event::<PlacementUndone>();
+
+        app.add_system(undo_placement.label(PieceUndoSystems).after(PieceSystems));
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemLabel)]
+pub struct PieceUndoSystems;
+
+/// Asks for the last placed piece to be taken back out of its bag.
+pub struct UndoPlacement;
+
+pub struct PlacementUndone {
+    pub piece: Entity,
+    pub bag: Entity,
+}
+
+#[derive(Default)]
+struct PlacementHistory {
+    last: Option<(Entity, Entity)>,
+    undone_bags: SmallVec<[Entity; 4]>,
+}
+
+/// Each bag lets one of the player's placements into it be undone for as long
+/// as it stays on screen.
+fn undo_placement(
+    mut history: Local<PlacementHistory>,
+    mut level_started: EventReader<LevelStarted>,
+    mut piece_placements: EventReader<PiecePlaced>,
+    mut undo_requests: EventReader<UndoPlacement>,
+    actions: Res<Actions>,
+    mut undone_placements: EventWriter<PlacementUndone>,
+    gb9000: Res<GroceryBagger9000>,
+    bags: Query<(), (With<BagMarker>, Without<Exiting>)>,
+    belt: Query<&BeltPieceIds, With<ConveyorBeltMarker>>,
+) {
+    if level_started.iter().count() > 0 {
+        *history = default();
+    }
+    if let Some(PiecePlaced { piece, bag, .. }) = piece_placements
+        .iter()
+        .filter(|placement| !placement.by_robot)
+        .last()
+    {
+        history.last = Some((*piece, *bag));
+    }
+
+    let requested = undo_requests.iter().count() > 0 || actions.just_pressed(Action::Undo);
+    if !requested || gb9000.state != Playing {
+        return;
+    }
+    // The level is about to end once the last piece is placed
+    if belt.get_single().map_or(false, |pieces| pieces.is_empty()) {
+        return;
+    }
+    let Some((piece, bag)) = history.last else {
+        return;
+    };
+    if history.undone_bags.contains(&bag) || !bags.contains(bag) {
+        return;
+    }
+
+    history.last = None;
+    history.undone_bags.push(bag);
+    undone_placements.send(PlacementUndone { piece, bag });
+}
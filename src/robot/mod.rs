@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 
+pub use placement::{
+    AdversarialStrategy, GreedyStrategy, Placement, PlacementBags, PlacementStrategy, RobotPiece,
+    RobotStrategy, ScoreMaximizingStrategy,
+};
 pub use spawn::{RobotMarker, RobotSpawner};
 use timing::RobotTimingPlugin;
 pub use timing::{RobotTargetMarker, RobotTiming};
 
+mod placement;
 mod spawn;
 mod timing;
 
@@ -19,11 +24,46 @@ impl Plugin for RobotPlugin {
 
 pub struct RobotOptions {
     pub enabled: bool,
+    /// Used by levels that don't pick their own strategy.
+    pub strategy: PlacementStrategy,
+    pub difficulty: RobotDifficulty,
+}
+
+/// Easier robots take longer to place their pieces.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum RobotDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
 }
 
 impl Default for RobotOptions {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            strategy: default(),
+            difficulty: default(),
+        }
+    }
+}
+
+impl RobotDifficulty {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+
+    const fn time_scale(self) -> f32 {
+        match self {
+            Self::Easy => 1.5,
+            Self::Normal => 1.,
+            Self::Hard => 0.5,
+        }
     }
 }
 
@@ -36,7 +76,8 @@ fn init_robot(app: &mut App) {
 fn init_robot(app: &mut App) {
     use rand::{thread_rng, Rng};
 
-    let mut options = RobotOptions::default();
-    options.enabled = thread_rng().gen();
-    app.insert_resource(options);
+    app.insert_resource(RobotOptions {
+        enabled: thread_rng().gen(),
+        ..default()
+    });
 }
@@ -0,0 +1,363 @@
+use std::cmp::Reverse;
+
+use bevy::{math::const_vec3, prelude::*};
+use serde::Deserialize;
+use smallvec::SmallVec;
+
+use crate::{
+    bags::{BagGrid, BlockPositions},
+    levels::score_bag,
+    nominos::{Nomino, NominoColor, NominoGeometry, DEG_180, DEG_90, DEG_MIRRORED},
+};
+
+/// Decides where the robo-bagger puts its pieces.
+pub trait RobotStrategy: Send + Sync + 'static {
+    fn find_placement(
+        &self,
+        bags: &PlacementBags,
+        piece: &RobotPiece,
+        player_pieces: &[(Nomino, NominoColor)],
+    ) -> Option<Placement>;
+}
+
+/// The built-in strategies, as chosen by levels and the robot options.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub enum PlacementStrategy {
+    /// Drops the piece as-is into the lowest spot it fits in.
+    #[default]
+    Greedy,
+    /// Tries every orientation in every spot of every bag and picks the one
+    /// that raises a bag's score the most.
+    ScoreMaximizing,
+    /// Takes the spot the player could score the most with.
+    Adversarial,
+}
+
+pub type PlacementBags<'a> = [(Entity, &'a GlobalTransform, &'a BagGrid)];
+
+#[derive(Debug, Copy, Clone)]
+pub struct RobotPiece {
+    pub id: Entity,
+    pub nomino: Nomino,
+    pub color: NominoColor,
+    pub rotation: Quat,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Placement {
+    pub bag: Entity,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub bag_position: Vec3,
+}
+
+pub struct GreedyStrategy;
+
+pub struct ScoreMaximizingStrategy;
+
+pub struct AdversarialStrategy;
+
+struct Candidate {
+    placement: Placement,
+    blocks: BlockPositions,
+    gain: i32,
+    top: i32,
+}
+
+impl PlacementStrategy {
+    #[must_use]
+    pub const fn robot_strategy(self) -> &'static dyn RobotStrategy {
+        match self {
+            Self::Greedy => &GreedyStrategy,
+            Self::ScoreMaximizing => &ScoreMaximizingStrategy,
+            Self::Adversarial => &AdversarialStrategy,
+        }
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Greedy => "Greedy",
+            Self::ScoreMaximizing => "Score maximizing",
+            Self::Adversarial => "Adversarial",
+        }
+    }
+}
+
+impl RobotStrategy for GreedyStrategy {
+    fn find_placement(
+        &self,
+        bags: &PlacementBags,
+        piece: &RobotPiece,
+        _: &[(Nomino, NominoColor)],
+    ) -> Option<Placement> {
+        let max_rows = bags.iter().map(|b| b.2.size().height()).max()?;
+        for row in 0..max_rows {
+            for &(bag, bag_coords, grid) in bags {
+                if row >= grid.size().height() {
+                    continue;
+                }
+
+                for col in 0..grid.size().width() {
+                    let blocks = shifted(piece.nomino.blocks(piece.rotation).into_iter(), col, row);
+                    if grid.straddles(&blocks) || grid.overlaps(&blocks) {
+                        continue;
+                    }
+
+                    return Some(Placement {
+                        bag,
+                        position: cell_position(bag_coords, grid, col, row),
+                        rotation: piece.rotation,
+                        bag_position: bag_coords.translation,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+impl RobotStrategy for ScoreMaximizingStrategy {
+    fn find_placement(
+        &self,
+        bags: &PlacementBags,
+        piece: &RobotPiece,
+        _: &[(Nomino, NominoColor)],
+    ) -> Option<Placement> {
+        best_candidate(bags, piece.id, piece.nomino, piece.color).map(|c| c.placement)
+    }
+}
+
+impl RobotStrategy for AdversarialStrategy {
+    fn find_placement(
+        &self,
+        bags: &PlacementBags,
+        piece: &RobotPiece,
+        player_pieces: &[(Nomino, NominoColor)],
+    ) -> Option<Placement> {
+        let Some(player_best) = player_pieces
+            .iter()
+            .filter_map(|&(nomino, color)| best_candidate(bags, piece.id, nomino, color))
+            .min_by_key(|c| (Reverse(c.gain), c.top)) else {
+            return ScoreMaximizingStrategy.find_placement(bags, piece, player_pieces);
+        };
+
+        // Cover as much of the player's spot as possible, ruining bags along the way
+        candidates(bags, piece.id, piece.nomino, piece.color)
+            .into_iter()
+            .min_by_key(|c| {
+                let blocked = if c.placement.bag == player_best.placement.bag {
+                    c.blocks
+                        .iter()
+                        .filter(|block| player_best.blocks.contains(block))
+                        .count()
+                } else {
+                    0
+                };
+                (Reverse(blocked), c.gain, c.top)
+            })
+            .map(|c| c.placement)
+    }
+}
+
+/// Prefers lower placements on ties to keep room for later pieces.
+fn best_candidate(
+    bags: &PlacementBags,
+    piece: Entity,
+    nomino: Nomino,
+    color: NominoColor,
+) -> Option<Candidate> {
+    candidates(bags, piece, nomino, color)
+        .into_iter()
+        .min_by_key(|c| (Reverse(c.gain), c.top))
+}
+
+/// Every supported spot the piece fits in, in any orientation, along with
+/// how much placing it there would change the bag's score.
+fn candidates(
+    bags: &PlacementBags,
+    piece: Entity,
+    nomino: Nomino,
+    color: NominoColor,
+) -> Vec<Candidate> {
+    let orientations = unique_orientations(nomino);
+
+    let mut candidates = Vec::new();
+    for &(bag, bag_coords, grid) in bags {
+        let bag_size = grid.size();
+        let current_score = i32::from(score_bag(&grid.colors(), bag_size.capacity()));
+        let mut scratch = grid.clone();
+
+        for &(rotation, ref shape) in &orientations {
+            for row in 0..bag_size.height() {
+                for col in 0..bag_size.width() {
+                    let blocks = shifted(shape.iter().copied(), col, row);
+                    if grid.straddles(&blocks) || grid.overlaps(&blocks) || !grid.supports(&blocks)
+                    {
+                        continue;
+                    }
+
+                    scratch.insert(piece, color, &blocks);
+                    let gain = i32::from(score_bag(&scratch.colors(), bag_size.capacity()))
+                        - current_score;
+                    scratch.remove(piece);
+                    let top = blocks.iter().map(|b| b.y).max().unwrap();
+
+                    candidates.push(Candidate {
+                        placement: Placement {
+                            bag,
+                            position: cell_position(bag_coords, grid, col, row),
+                            rotation,
+                            bag_position: bag_coords.translation,
+                        },
+                        blocks,
+                        gain,
+                        top,
+                    });
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// All four turns of the piece and their mirror images, skipping those that
+/// cover the same blocks as an earlier one.
+fn unique_orientations(nomino: Nomino) -> SmallVec<[(Quat, BlockPositions); 8]> {
+    let turns = [Quat::IDENTITY, *DEG_90, *DEG_180, DEG_90.inverse()];
+
+    let mut orientations = SmallVec::<[(Quat, BlockPositions); 8]>::new();
+    for rotation in turns
+        .into_iter()
+        .chain(turns.map(|turn| *DEG_MIRRORED * turn))
+    {
+        let mut shape = nomino.blocks(rotation);
+        shape.sort_unstable_by_key(|block| (block.x, block.y));
+
+        if orientations.iter().all(|(_, other)| *other != shape) {
+            orientations.push((rotation, shape));
+        }
+    }
+    orientations
+}
+
+fn shifted(blocks: impl Iterator<Item = IVec2>, col: u8, row: u8) -> BlockPositions {
+    let offset = IVec2::new(col.into(), row.into());
+    blocks.map(|block| block + offset).collect()
+}
+
+fn cell_position(bag_coords: &GlobalTransform, grid: &BagGrid, col: u8, row: u8) -> Vec3 {
+    bag_coords.translation - grid.size().origin()
+        + const_vec3!([0.5, 0.5, 0.])
+        + Vec3::new(f32::from(col), f32::from(row), 0.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bags::BagSize;
+
+    fn piece(nomino: Nomino, color: NominoColor, rotation: Quat) -> RobotPiece {
+        RobotPiece {
+            id: Entity::from_raw(100),
+            nomino,
+            color,
+            rotation,
+        }
+    }
+
+    fn placed_blocks(placement: Placement, grid: &BagGrid, nomino: Nomino) -> BlockPositions {
+        let mut blocks = grid.piece_blocks(
+            placement.bag_position,
+            nomino,
+            Transform::from_translation(placement.position).with_rotation(placement.rotation),
+        );
+        blocks.sort_unstable_by_key(|block| (block.x, block.y));
+        blocks
+    }
+
+    #[test]
+    fn greedy_placement_keeps_the_piece_rotation() {
+        let grid = BagGrid::new(BagSize::new(3, 4));
+        let bag = GlobalTransform::from_translation(grid.size().origin());
+
+        let placement = GreedyStrategy
+            .find_placement(
+                &[(Entity::from_raw(0), &bag, &grid)],
+                &piece(Nomino::TrominoL, NominoColor::Gold, *DEG_180),
+                &[],
+            )
+            .unwrap();
+
+        assert_eq!(placement.rotation, *DEG_180);
+        assert_eq!(
+            placed_blocks(placement, &grid, Nomino::TrominoL).as_slice(),
+            [IVec2::new(0, 1), IVec2::new(1, 0), IVec2::new(1, 1)]
+        );
+    }
+
+    #[test]
+    fn best_placement_avoids_holes() {
+        let grid = BagGrid::new(BagSize::new(3, 4));
+        let bag = GlobalTransform::from_translation(grid.size().origin());
+
+        let placement = ScoreMaximizingStrategy
+            .find_placement(
+                &[(Entity::from_raw(0), &bag, &grid)],
+                &piece(Nomino::TrominoL, NominoColor::Gold, *DEG_180),
+                &[],
+            )
+            .unwrap();
+
+        assert_eq!(
+            placed_blocks(placement, &grid, Nomino::TrominoL).as_slice(),
+            [IVec2::new(0, 0), IVec2::new(0, 1), IVec2::new(1, 0)]
+        );
+    }
+
+    #[test]
+    fn best_placement_matches_colors() {
+        let mut gold = BagGrid::new(BagSize::new(3, 4));
+        gold.insert(Entity::from_raw(0), NominoColor::Gold, &[IVec2::new(0, 0)]);
+        let mut pink = BagGrid::new(BagSize::new(3, 4));
+        pink.insert(Entity::from_raw(0), NominoColor::Pink, &[IVec2::new(0, 0)]);
+        let gold_bag = GlobalTransform::from_translation(gold.size().origin());
+        let pink_bag = GlobalTransform::from_translation(Vec3::X * 10. + pink.size().origin());
+
+        let placement = ScoreMaximizingStrategy
+            .find_placement(
+                &[
+                    (Entity::from_raw(1), &pink_bag, &pink),
+                    (Entity::from_raw(2), &gold_bag, &gold),
+                ],
+                &piece(Nomino::TrominoStraight, NominoColor::Gold, Quat::IDENTITY),
+                &[],
+            )
+            .unwrap();
+
+        assert_eq!(placement.bag, Entity::from_raw(2));
+    }
+
+    #[test]
+    fn adversary_takes_the_players_spot() {
+        let mut gold = BagGrid::new(BagSize::new(3, 4));
+        gold.insert(Entity::from_raw(0), NominoColor::Gold, &[IVec2::new(0, 0)]);
+        let mut pink = BagGrid::new(BagSize::new(3, 4));
+        pink.insert(Entity::from_raw(0), NominoColor::Pink, &[IVec2::new(0, 0)]);
+        let gold_bag = GlobalTransform::from_translation(gold.size().origin());
+        let pink_bag = GlobalTransform::from_translation(Vec3::X * 10. + pink.size().origin());
+
+        let placement = AdversarialStrategy
+            .find_placement(
+                &[
+                    (Entity::from_raw(1), &pink_bag, &pink),
+                    (Entity::from_raw(2), &gold_bag, &gold),
+                ],
+                &piece(Nomino::TrominoStraight, NominoColor::Pink, Quat::IDENTITY),
+                &[(Nomino::TrominoStraight, NominoColor::Gold)],
+            )
+            .unwrap();
+
+        assert_eq!(placement.bag, Entity::from_raw(2));
+    }
+}
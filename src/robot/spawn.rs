@@ -1,15 +1,18 @@
 use bevy::prelude::*;
 
-use crate::{levels::LevelMarker, robot::RobotTiming};
+use crate::{
+    levels::LevelMarker,
+    robot::{PlacementStrategy, RobotTiming},
+};
 
 #[derive(Default, Component)]
 pub struct RobotMarker;
 
 pub trait RobotSpawner<'w, 's> {
-    fn spawn_robot(&mut self);
+    fn spawn_robot(&mut self, strategy: Option<PlacementStrategy>);
 }
 
-#[derive(Default, Bundle)]
+#[derive(Bundle)]
 struct RobotBundle {
     #[bundle]
     transforms: TransformBundle,
@@ -19,7 +22,12 @@ struct RobotBundle {
 }
 
 impl<'w, 's> RobotSpawner<'w, 's> for Commands<'w, 's> {
-    fn spawn_robot(&mut self) {
-        self.spawn_and_forget(RobotBundle::default());
+    fn spawn_robot(&mut self, strategy: Option<PlacementStrategy>) {
+        self.spawn_and_forget(RobotBundle {
+            transforms: default(),
+            level_marker: default(),
+            robot_marker: default(),
+            robot: RobotTiming::new(strategy),
+        });
     }
 }
@@ -1,35 +1,26 @@
 use std::{cmp::min, time::Duration};
 
-use bevy::{ecs::schedule::ShouldRun, math::const_vec3, prelude::*};
+use bevy::{ecs::schedule::ShouldRun, prelude::*};
 use bevy_prototype_lyon::prelude::DrawMode;
-use bevy_rapier3d::prelude::{Collider, CollisionGroups, RapierContext};
 use bevy_tweening::{AnimationSystem, Animator, TweenCompleted};
+use smallvec::SmallVec;
 
 use crate::{
     animations::{AnimationComponentsBundle, AnimationEvent, GameSpeed, Target},
-    bags::{
-        BagMarker, BagReplacementDetectionSystems, BagSize, BAG_FLOOR_COLLIDER_GROUP,
-        BAG_WALLS_COLLIDER_GROUP,
-    },
+    bags::{BagGrid, BagMarker, BagReplacementDetectionSystems},
     conveyor_belt::BeltMovementSystems,
+    gb9000::{GameState::Playing, GroceryBagger9000},
     levels::{LevelFinished, LevelMarker, ScoringSystems},
-    nominos::{
-        Nomino, NominoBundle, NominoColor, PiecePlaced, PieceSystems, Selected,
-        NOMINO_COLLIDER_GROUP,
+    nominos::{Nomino, NominoBundle, NominoColor, PiecePlaced, PieceSystems, Selectable},
+    robot::{
+        placement::{Placement, PlacementStrategy, RobotPiece, RobotStrategy},
+        spawn::RobotMarker,
+        RobotOptions,
     },
-    robot::{spawn::RobotMarker, RobotOptions},
 };
 
 const PLACEMENT_TTL: Duration = Duration::from_secs(6);
 const MAX_TTL: Duration = Duration::from_secs(10);
-const INVALID_PLACEMENT_GROUPS: CollisionGroups = CollisionGroups {
-    memberships: BAG_FLOOR_COLLIDER_GROUP.memberships
-        | BAG_WALLS_COLLIDER_GROUP.memberships
-        | NOMINO_COLLIDER_GROUP.memberships,
-    filters: BAG_FLOOR_COLLIDER_GROUP.filters
-        | BAG_WALLS_COLLIDER_GROUP.filters
-        | NOMINO_COLLIDER_GROUP.filters,
-};
 
 pub struct RobotTimingPlugin;
 
@@ -52,7 +43,7 @@ impl Plugin for RobotTimingPlugin {
         );
         app.add_system(
             place_piece
-                .with_run_criteria(run_if_robot_is_enabled)
+                .with_run_criteria(run_if_robot_is_playing)
                 .after(PieceSystems)
                 .after(ScoringSystems)
                 .after(BagReplacementDetectionSystems)
@@ -61,7 +52,7 @@ impl Plugin for RobotTimingPlugin {
         );
         app.add_system(
             show_target_placement
-                .with_run_criteria(run_if_robot_is_enabled)
+                .with_run_criteria(run_if_robot_is_playing)
                 .after(accumulate_left_over_time)
                 .after(place_piece),
         );
@@ -72,6 +63,8 @@ impl Plugin for RobotTimingPlugin {
 pub struct RobotTiming {
     ttl: Timer,
     continue_trying: bool,
+    /// The level's own strategy, which wins over the robot options.
+    strategy: Option<PlacementStrategy>,
 }
 
 #[derive(Component)]
@@ -80,13 +73,19 @@ pub struct RobotTargetMarker;
 #[derive(Component)]
 struct IndicatorPieceMarker;
 
-impl Default for RobotTiming {
-    fn default() -> Self {
+impl RobotTiming {
+    #[must_use]
+    pub fn new(strategy: Option<PlacementStrategy>) -> Self {
         Self {
             ttl: Timer::new(PLACEMENT_TTL, false),
             continue_trying: false,
+            strategy,
         }
     }
+
+    fn strategy(&self, options: &RobotOptions) -> &'static dyn RobotStrategy {
+        self.strategy.unwrap_or(options.strategy).robot_strategy()
+    }
 }
 
 fn run_if_robot_is_enabled(options: Res<RobotOptions>) -> ShouldRun {
@@ -97,6 +96,18 @@ fn run_if_robot_is_enabled(options: Res<RobotOptions>) -> ShouldRun {
     }
 }
 
+/// The robot only keeps time and places pieces while the level is being played.
+fn run_if_robot_is_playing(
+    options: Res<RobotOptions>,
+    gb9000: Res<GroceryBagger9000>,
+) -> ShouldRun {
+    if options.enabled && gb9000.state == Playing {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
 fn remove_robot_on_disable(
     mut commands: Commands,
     robot_entities: Query<Entity, With<IndicatorPieceMarker>>,
@@ -117,9 +128,12 @@ fn accumulate_left_over_time(
     if let Ok(mut robot) = timing.get_single_mut() {
         robot.continue_trying = false;
 
-        let ttl = &mut robot.ttl;
-        ttl.set_duration(min(MAX_TTL, ttl.duration() - ttl.elapsed() + PLACEMENT_TTL));
-        ttl.reset();
+        let duration = min(
+            MAX_TTL,
+            robot.ttl.duration() - robot.ttl.elapsed() + PLACEMENT_TTL,
+        );
+        robot.ttl.set_duration(duration);
+        robot.ttl.reset();
     }
 }
 
@@ -129,29 +143,28 @@ fn show_target_placement(
     mut spawned: Local<Option<(Entity, Entity)>>,
     mut level_finished: EventReader<LevelFinished>,
     mut completed_animations: EventReader<TweenCompleted>,
+    options: Res<RobotOptions>,
     timing: Query<&RobotTiming, With<RobotMarker>>,
     bags: Query<
-        (Entity, &GlobalTransform, &BagSize),
+        (Entity, &GlobalTransform, &BagGrid),
         (
             With<BagMarker>,
             Without<RobotTargetMarker>,
             Without<Animator<Transform>>,
         ),
     >,
-    selected_piece: Query<(), With<Selected>>,
     target_piece: Query<
         (
             Entity,
             &GlobalTransform,
             Option<&Target<Transform>>,
-            &Collider,
             &Nomino,
             &NominoColor,
             ChangeTrackers<RobotTargetMarker>,
         ),
         (With<RobotTargetMarker>, Without<BagMarker>),
     >,
-    rapier_context: Res<RapierContext>,
+    player_pieces: Query<(&Nomino, &NominoColor), (With<Selectable>, Without<RobotTargetMarker>)>,
 ) {
     if level_finished.iter().count() > 0 {
         *spawned = None;
@@ -172,7 +185,7 @@ fn show_target_placement(
         maybe_despawn();
         return;
     };
-    let (target_id, piece_position, target, collider, nomino, color, target_changes) =
+    let (target_id, piece_position, target, nomino, color, target_changes) =
         if let Ok(p) = target_piece.get_single() {
             p
         } else {
@@ -201,7 +214,7 @@ fn show_target_placement(
         }
     }
 
-    if !target_changes.is_changed() && spawned_copy.is_some() {
+    if !target_changes.is_changed() && !options.is_changed() && spawned_copy.is_some() {
         if let Some((_, indicator)) = spawned_copy {
             let mut colors = indicator_piece.get_mut(indicator).unwrap().0;
             if let DrawMode::Outlined {
@@ -214,17 +227,29 @@ fn show_target_placement(
         return;
     }
 
-    if let Some((_, position, _)) = find_robot_piece_placement(
-        bags,
-        selected_piece,
-        &piece_position,
-        collider,
-        &rapier_context,
+    let bags = bags.iter().collect::<SmallVec<[_; 3]>>();
+    if let Some(Placement {
+        position, rotation, ..
+    }) = robot.strategy(&options).find_placement(
+        &bags,
+        &RobotPiece {
+            id: target_id,
+            nomino: *nomino,
+            color: *color,
+            rotation: piece_position.rotation,
+        },
+        &player_pieces
+            .iter()
+            .map(|(n, c)| (*n, *c))
+            .collect::<SmallVec<[_; 3]>>(),
     ) {
+        let indicator_position = piece_position
+            .with_translation(position)
+            .with_rotation(rotation);
         if let Some((target, indicator)) = spawned_copy && target == target_id {
             let (mut colors, mut transform) = indicator_piece.get_mut(indicator).unwrap();
 
-            *transform = piece_position.with_translation(position).into();
+            *transform = indicator_position.into();
             if let DrawMode::Outlined {
                 ref mut fill_mode, ..
             } = *colors
@@ -237,7 +262,7 @@ fn show_target_placement(
                 target_id,
                 commands
                     .spawn_bundle(NominoBundle::new(
-                        piece_position.with_translation(position).into(),
+                        indicator_position.into(),
                         *nomino,
                         *color,
                         render_color(),
@@ -256,41 +281,47 @@ fn place_piece(
     mut commands: Commands,
     time: Res<Time>,
     game_speed: Res<GameSpeed>,
+    options: Res<RobotOptions>,
     mut timing: Query<&mut RobotTiming, With<RobotMarker>>,
     bags: Query<
-        (Entity, &GlobalTransform, &BagSize),
+        (Entity, &GlobalTransform, &BagGrid),
         (
             With<BagMarker>,
             Without<RobotTargetMarker>,
             Without<Animator<Transform>>,
         ),
     >,
-    selected_piece: Query<(), With<Selected>>,
     mut target_piece: Query<
         (
             Entity,
             &GlobalTransform,
             &mut Transform,
             Option<&Target<Transform>>,
-            &Collider,
+            &Nomino,
+            &NominoColor,
         ),
         (With<RobotTargetMarker>, Without<BagMarker>),
     >,
+    player_pieces: Query<(&Nomino, &NominoColor), (With<Selectable>, Without<RobotTargetMarker>)>,
     mut piece_placements: EventWriter<PiecePlaced>,
-    rapier_context: Res<RapierContext>,
 ) {
     let mut robot = if let Ok(r) = timing.get_single_mut() {
         r
     } else {
         return;
     };
-    robot.ttl.tick(time.delta().mul_f32(**game_speed));
+    // Difficulty only changes how fast the robot's clock runs, so switching it
+    // mid-level takes effect straight away.
+    robot.ttl.tick(
+        time.delta()
+            .mul_f32(**game_speed / options.difficulty.time_scale()),
+    );
     if !robot.continue_trying && !robot.ttl.just_finished() {
         return;
     }
     robot.continue_trying = true;
 
-    let (piece, piece_position, mut local_piece_position, target, collider) =
+    let (piece, piece_position, mut local_piece_position, target, nomino, color) =
         if let Ok(p) = target_piece.get_single_mut() {
             p
         } else {
@@ -301,16 +332,33 @@ fn place_piece(
         piece_position.rotation = target.rotation;
     }
 
-    if let Some((bag, mut position, bag_position)) = find_robot_piece_placement(
-        bags,
-        selected_piece,
-        &piece_position,
-        collider,
-        &rapier_context,
+    let bags = bags.iter().collect::<SmallVec<[_; 3]>>();
+    if let Some(Placement {
+        bag,
+        mut position,
+        rotation,
+        bag_position,
+    }) = robot.strategy(&options).find_placement(
+        &bags,
+        &RobotPiece {
+            id: piece,
+            nomino: *nomino,
+            color: *color,
+            rotation: piece_position.rotation,
+        },
+        &player_pieces
+            .iter()
+            .map(|(n, c)| (*n, *c))
+            .collect::<SmallVec<[_; 3]>>(),
     ) {
         position.z = piece_position.translation.z;
         local_piece_position.translation = position - bag_position;
-        piece_placements.send(PiecePlaced { piece, bag });
+        local_piece_position.rotation = rotation;
+        piece_placements.send(PiecePlaced {
+            piece,
+            bag,
+            by_robot: true,
+        });
 
         commands.entity(bag).add_child(piece);
         commands
@@ -318,46 +366,3 @@ fn place_piece(
             .remove_bundle::<AnimationComponentsBundle<Transform>>();
     }
 }
-
-fn find_robot_piece_placement(
-    bags: Query<
-        (Entity, &GlobalTransform, &BagSize),
-        (
-            With<BagMarker>,
-            Without<RobotTargetMarker>,
-            Without<Animator<Transform>>,
-        ),
-    >,
-    ignore: Query<(), With<Selected>>,
-    target_piece: &GlobalTransform,
-    collider: &Collider,
-    rapier_context: &RapierContext,
-) -> Option<(Entity, Vec3, Vec3)> {
-    let max_rows = bags.iter().map(|b| b.2.height()).sum();
-    for row in 0..max_rows {
-        for (bag, bag_coords, bag_size) in bags.iter() {
-            if row >= bag_size.height() {
-                continue;
-            }
-
-            let block_origin =
-                bag_coords.translation - bag_size.origin() + const_vec3!([0.5, 0.5, 0.]);
-            for i in 0..bag_size.width() {
-                let position = block_origin + Vec3::new(f32::from(i), f32::from(row), 0.);
-                let intersects = rapier_context.intersection_with_shape(
-                    position,
-                    target_piece.rotation,
-                    collider,
-                    INVALID_PLACEMENT_GROUPS.into(),
-                    Some(&|id| !ignore.contains(id)),
-                );
-                if intersects.is_some() {
-                    continue;
-                }
-
-                return Some((bag, position, bag_coords.translation));
-            }
-        }
-    }
-    None
-}
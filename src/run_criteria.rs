@@ -1,11 +1,121 @@
 use bevy::{ecs::schedule::ShouldRun, prelude::*};
 
-use crate::levels::LevelStarted;
+use crate::gb9000::{GameState, GroceryBagger9000};
 
-pub fn run_if_level_started(mut level_loaded: EventReader<LevelStarted>) -> ShouldRun {
-    if level_loaded.iter().count() > 0 {
+pub fn run_if_playing(gb9000: Res<GroceryBagger9000>) -> ShouldRun {
+    if gb9000.state == GameState::Playing {
         ShouldRun::Yes
     } else {
         ShouldRun::No
     }
 }
+
+/// Runs once each time the game moves into one of the states from outside
+/// them.
+pub fn run_on_enter(
+    states: &'static [GameState],
+) -> impl FnMut(Res<GroceryBagger9000>, Local<Option<GameState>>) -> ShouldRun {
+    move |gb9000, mut prev_state| {
+        let prev_state = prev_state.replace(gb9000.state);
+        let was_in = prev_state.map_or(false, |state| states.contains(&state));
+        if !was_in && states.contains(&gb9000.state) {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
+/// Runs once each time the game moves out of the states.
+pub fn run_on_exit(
+    states: &'static [GameState],
+) -> impl FnMut(Res<GroceryBagger9000>, Local<Option<GameState>>) -> ShouldRun {
+    move |gb9000, mut prev_state| {
+        let prev_state = prev_state.replace(gb9000.state);
+        let was_in = prev_state.map_or(false, |state| states.contains(&state));
+        if was_in && !states.contains(&gb9000.state) {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::schedule::IntoRunCriteria;
+
+    use super::*;
+    use crate::gb9000::GameState::{MainMenu, Paused, Playing, Settings};
+
+    #[derive(Default)]
+    struct Runs(u32);
+
+    /// Whether a system with the run criteria ran on each frame spent in the
+    /// given states.
+    fn runs<Marker>(run_criteria: impl IntoRunCriteria<Marker>, states: &[GameState]) -> Vec<bool> {
+        let mut app = App::new();
+        app.init_resource::<GroceryBagger9000>();
+        app.init_resource::<Runs>();
+        app.add_system((|mut runs: ResMut<Runs>| runs.0 += 1).with_run_criteria(run_criteria));
+
+        states
+            .iter()
+            .map(|&state| {
+                app.world.resource_mut::<GroceryBagger9000>().state = state;
+                let before = app.world.resource::<Runs>().0;
+                app.update();
+                app.world.resource::<Runs>().0 > before
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enter_runs_once_per_entry() {
+        assert_eq!(
+            runs(
+                run_on_enter(&[Playing]),
+                &[MainMenu, Playing, Playing, Paused, Playing]
+            ),
+            [false, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn enter_runs_when_starting_in_the_state() {
+        assert_eq!(
+            runs(run_on_enter(&[Playing]), &[Playing, Playing]),
+            [true, false]
+        );
+    }
+
+    #[test]
+    fn enter_ignores_moves_between_the_states() {
+        assert_eq!(
+            runs(
+                run_on_enter(&[Paused, Settings]),
+                &[Playing, Paused, Settings, Paused, Playing, Settings]
+            ),
+            [false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn exit_runs_once_per_exit() {
+        assert_eq!(
+            runs(
+                run_on_exit(&[Paused, Settings]),
+                &[Paused, Settings, Playing, Playing, Settings, MainMenu]
+            ),
+            [false, false, true, false, false, true]
+        );
+    }
+
+    #[test]
+    fn exit_never_runs_without_entering() {
+        assert_eq!(
+            runs(run_on_exit(&[Paused]), &[Playing, MainMenu]),
+            [false, false]
+        );
+    }
+}
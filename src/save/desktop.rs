@@ -0,0 +1,40 @@
+use std::{env, fs, path::PathBuf};
+
+use bevy::prelude::*;
+
+pub fn read_save() -> Option<String> {
+    fs::read_to_string(save_path()?).ok()
+}
+
+pub fn write_save(save: &str) {
+    let Some(path) = save_path() else {
+        warn!("Nowhere to save to");
+        return;
+    };
+
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, save));
+    if let Err(e) = written {
+        warn!("Failed to write save data to {path:?}: {e}");
+    }
+}
+
+fn save_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("grocery_bagger_9000").join("save.ron"))
+}
+
+/// The platform's directory for per-user application data.
+fn data_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    }
+}
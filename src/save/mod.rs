@@ -0,0 +1,261 @@
+use std::{collections::BTreeMap, mem};
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use desktop::{read_save, write_save};
+#[cfg(target_arch = "wasm32")]
+use web::{read_save, write_save};
+
+use crate::{
+    actions::Bindings,
+    animations::GameSpeed,
+    conveyor_belt::ConveyorBeltOptions,
+    gb9000::{
+        GameState::{GameComplete, LevelEnded},
+        GroceryBagger9000,
+    },
+    levels::{CurrentScore, LevelResult, LevelTransitionSystems},
+    robot::RobotOptions,
+    run_criteria::run_on_enter,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(read_save().map_or_else(default, |save| parse_save(&save)));
+        // Restored before anything else looks at the options
+        app.add_startup_system_to_stage(StartupStage::PreStartup, load_save);
+
+        app.add_system(
+            record_level_end
+                .with_run_criteria(run_on_enter(&[LevelEnded, GameComplete]))
+                .after(LevelTransitionSystems),
+        );
+        app.add_system(record_options);
+        app.add_system(store_save.after(record_level_end).after(record_options));
+    }
+}
+
+/// Everything that's kept between runs of the game.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    /// The furthest level the player has reached.
+    pub unlocked_level: u16,
+    pub levels: BTreeMap<u16, LevelRecord>,
+    pub all_time_points: usize,
+    pub robot_enabled: Option<bool>,
+    pub game_speed: Option<f32>,
+    pub num_pieces_selectable: Option<u8>,
+    pub bindings: Option<Bindings>,
+}
+
+/// The player's best run of a level.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LevelRecord {
+    pub best_score: usize,
+    pub stars: u8,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            unlocked_level: 1,
+            levels: default(),
+            all_time_points: 0,
+            robot_enabled: None,
+            game_speed: None,
+            num_pieces_selectable: None,
+            bindings: None,
+        }
+    }
+}
+
+fn load_save(
+    save: Res<SaveData>,
+    mut gb9000: ResMut<GroceryBagger9000>,
+    mut score: ResMut<CurrentScore>,
+    mut robot_options: ResMut<RobotOptions>,
+    mut game_speed: ResMut<GameSpeed>,
+    mut belt_options: ResMut<ConveyorBeltOptions>,
+    mut bindings: ResMut<Bindings>,
+) {
+    *gb9000 = GroceryBagger9000 {
+        current_level: save.unlocked_level.max(1),
+        ..*gb9000
+    };
+    *score = CurrentScore::carried_over(save.all_time_points);
+    robot_options.enabled = save.robot_enabled.unwrap_or(robot_options.enabled);
+    **game_speed = save.game_speed.unwrap_or(**game_speed);
+    belt_options.num_pieces_selectable = save
+        .num_pieces_selectable
+        .unwrap_or(belt_options.num_pieces_selectable);
+    if let Some(saved) = &save.bindings {
+        bindings.restore(saved);
+    }
+}
+
+fn parse_save(save: &str) -> SaveData {
+    ron::from_str(save).unwrap_or_else(|e| {
+        warn!("Ignoring unreadable save data: {e}");
+        default()
+    })
+}
+
+fn record_level_end(
+    mut save: ResMut<SaveData>,
+    gb9000: Res<GroceryBagger9000>,
+    score: Res<CurrentScore>,
+    result: Res<LevelResult>,
+) {
+    let level = gb9000.current_level;
+    let stars = result.stars.unwrap_or_default();
+
+    let record = save.levels.entry(level).or_default();
+    record.best_score = record.best_score.max(score.points);
+    record.stars = record.stars.max(stars);
+    save.unlocked_level = save.unlocked_level.max(level + 1);
+    save.all_time_points = score.all_time_points;
+}
+
+fn record_options(
+    mut started: Local<bool>,
+    mut save: ResMut<SaveData>,
+    robot_options: Res<RobotOptions>,
+    game_speed: Res<GameSpeed>,
+    belt_options: Res<ConveyorBeltOptions>,
+    bindings: Res<Bindings>,
+) {
+    // Everything looks changed the first time around, including the options
+    // that were just loaded
+    if !mem::replace(&mut *started, true) {
+        return;
+    }
+
+    if robot_options.is_changed() && save.robot_enabled != Some(robot_options.enabled) {
+        save.robot_enabled = Some(robot_options.enabled);
+    }
+    if game_speed.is_changed() && save.game_speed != Some(**game_speed) {
+        save.game_speed = Some(**game_speed);
+    }
+    if belt_options.is_changed()
+        && save.num_pieces_selectable != Some(belt_options.num_pieces_selectable)
+    {
+        save.num_pieces_selectable = Some(belt_options.num_pieces_selectable);
+    }
+    if bindings.is_changed() && save.bindings.as_ref() != Some(&*bindings) {
+        save.bindings = Some(bindings.clone());
+    }
+}
+
+fn store_save(save: Res<SaveData>) {
+    if !save.is_changed() || save.is_added() {
+        return;
+    }
+
+    match ron::ser::to_string_pretty(&*save, PrettyConfig::default()) {
+        Ok(save) => write_save(&save),
+        Err(e) => warn!("Failed to serialize save data: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_round_trip() {
+        let mut save = SaveData {
+            unlocked_level: 4,
+            all_time_points: 9000,
+            robot_enabled: Some(false),
+            bindings: Some(default()),
+            ..default()
+        };
+        save.levels.insert(
+            3,
+            LevelRecord {
+                best_score: 8000,
+                stars: 3,
+            },
+        );
+
+        let serialized = ron::ser::to_string_pretty(&save, PrettyConfig::default()).unwrap();
+        assert_eq!(ron::from_str::<SaveData>(&serialized).unwrap(), save);
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let save = ron::from_str::<SaveData>("(all_time_points: 42)").unwrap();
+
+        assert_eq!(save.unlocked_level, 1);
+        assert_eq!(save.all_time_points, 42);
+        assert!(save.levels.is_empty());
+    }
+
+    #[test]
+    fn loaded_options_are_not_saved_again() {
+        let mut app = app(SaveData {
+            robot_enabled: Some(false),
+            game_speed: Some(2.),
+            ..default()
+        });
+
+        app.update();
+        app.update();
+
+        assert!(!app.world.resource::<RobotOptions>().enabled);
+        assert_eq!(
+            *app.world.resource::<SaveData>(),
+            SaveData {
+                robot_enabled: Some(false),
+                game_speed: Some(2.),
+                ..default()
+            }
+        );
+    }
+
+    #[test]
+    fn only_changed_options_are_recorded() {
+        let mut app = app(default());
+        app.update();
+
+        app.world
+            .resource_mut::<ConveyorBeltOptions>()
+            .num_pieces_selectable = 1;
+        app.update();
+
+        assert_eq!(
+            *app.world.resource::<SaveData>(),
+            SaveData {
+                num_pieces_selectable: Some(1),
+                ..default()
+            }
+        );
+    }
+
+    fn app(save: SaveData) -> App {
+        let mut app = App::new();
+
+        app.insert_resource(save);
+        app.init_resource::<GroceryBagger9000>();
+        app.init_resource::<CurrentScore>();
+        app.init_resource::<RobotOptions>();
+        app.init_resource::<GameSpeed>();
+        app.init_resource::<ConveyorBeltOptions>();
+        app.init_resource::<Bindings>();
+        app.add_startup_system_to_stage(StartupStage::PreStartup, load_save);
+        app.add_system(record_options);
+
+        app
+    }
+}
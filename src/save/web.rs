@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+const SAVE_KEY: &str = "grocery_bagger_9000_save";
+
+pub fn read_save() -> Option<String> {
+    get_item(SAVE_KEY).ok().flatten()
+}
+
+pub fn write_save(save: &str) {
+    if let Err(e) = set_item(SAVE_KEY, save) {
+        warn!("Failed to write save data: {e:?}");
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(catch, js_namespace = localStorage, js_name = getItem)]
+    fn get_item(key: &str) -> Result<Option<String>, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = localStorage, js_name = setItem)]
+    fn set_item(key: &str, value: &str) -> Result<(), JsValue>;
+}
@@ -6,13 +6,19 @@ pub const HUD_FONT_SIZE: f32 = 32.;
 pub const IN_GAME_MENU_FONT_SIZE: f32 = 20.;
 pub const TITLE_FONT_SIZE: f32 = 48.;
 pub const MENU_FONT_SIZE: f32 = 32.;
+pub const LEVEL_SELECT_FONT_SIZE: f32 = 16.;
+pub const CONTROLS_FONT_SIZE: f32 = 18.;
 
 pub const BUTTON_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 pub const NORMAL_BUTTON: Color = hex_color!(101, 118, 205);
 pub const HOVERED_BUTTON: Color = hex_color!(138, 161, 238);
 pub const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+pub const LOCKED_BUTTON: Color = Color::rgba(0.9, 0.9, 0.9, 0.2);
 
 pub const SCORE_COLOR: Color = hex_color!(221, 111, 46);
 pub const TITLE_COLOR: Color = BUTTON_COLOR;
+pub const EARNED_STAR_COLOR: Color = hex_color!(0xD6, 0xC5, 0x42);
+pub const PAUSE_OVERLAY_COLOR: Color = Color::rgba(0., 0., 0., 0.6);
+pub const MISSING_STAR_COLOR: Color = Color::rgba(0.9, 0.9, 0.9, 0.2);
 
 pub const PRIMARY_FONT: &str = "fonts/FiraSans-Bold.ttf";
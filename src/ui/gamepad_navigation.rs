@@ -0,0 +1,111 @@
+use bevy::{prelude::*, ui::UiSystem};
+use smallvec::SmallVec;
+
+use crate::{
+    gamepad_utils::{gamepad_just_pressed, GamepadDirection},
+    gb9000::{GameState::Playing, GroceryBagger9000},
+    ui::in_game_menu::InGameMenuButton,
+};
+
+pub struct GamepadNavigationPlugin;
+
+impl Plugin for GamepadNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, navigate_menus.after(UiSystem::Focus));
+    }
+}
+
+#[derive(Default)]
+struct MenuFocus {
+    focused: Option<Entity>,
+    clicked: SmallVec<[Entity; 1]>,
+}
+
+/// Lets gamepads move between menu buttons and click them. The focused
+/// button looks hovered.
+fn navigate_menus(
+    mut focus: Local<MenuFocus>,
+    mut direction: Local<GamepadDirection>,
+    time: Res<Time>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gb9000: Res<GroceryBagger9000>,
+    mut buttons: Query<
+        (Entity, &GlobalTransform, &mut Interaction),
+        (With<Button>, Without<InGameMenuButton>),
+    >,
+) {
+    // Clicks only last a frame since no mouse release will come to undo them
+    for button in focus.clicked.drain(..) {
+        if let Ok((_, _, mut interaction)) = buttons.get_mut(button) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    let step = direction.step(&time, &gamepads, &gamepad_buttons, &gamepad_axes);
+    let pressed = gamepad_just_pressed(&gamepads, &gamepad_buttons, GamepadButtonType::South);
+    if gb9000.state == Playing {
+        focus.focused = None;
+        return;
+    }
+
+    let focused = match focus.focused {
+        Some(focused) if buttons.contains(focused) => {
+            if step == IVec2::ZERO {
+                focused
+            } else {
+                next_button(&buttons, focused, step.as_vec2()).unwrap_or(focused)
+            }
+        }
+        // Start from the top left button whenever a new menu shows up
+        _ if step != IVec2::ZERO || pressed => {
+            let Some((top_left, ..)) = buttons.iter().min_by(|(_, a, _), (_, b, _)| {
+                b.translation
+                    .y
+                    .total_cmp(&a.translation.y)
+                    .then(a.translation.x.total_cmp(&b.translation.x))
+            }) else {
+                return;
+            };
+            top_left
+        }
+        _ => return,
+    };
+    focus.focused = Some(focused);
+
+    let (_, _, mut interaction) = buttons.get_mut(focused).unwrap();
+    if pressed {
+        *interaction = Interaction::Clicked;
+        focus.clicked.push(focused);
+    } else if *interaction == Interaction::None {
+        *interaction = Interaction::Hovered;
+    }
+}
+
+/// Finds the closest button in the given direction, favoring ones that are
+/// lined up with the current one.
+fn next_button(
+    buttons: &Query<
+        (Entity, &GlobalTransform, &mut Interaction),
+        (With<Button>, Without<InGameMenuButton>),
+    >,
+    from: Entity,
+    direction: Vec2,
+) -> Option<Entity> {
+    let direction = direction.normalize();
+    let (_, from_position, _) = buttons.get(from).unwrap();
+    let from_position = from_position.translation.truncate();
+
+    buttons
+        .iter()
+        .filter(|(id, ..)| *id != from)
+        .filter_map(|(id, position, _)| {
+            let offset = position.translation.truncate() - from_position;
+            let along = offset.dot(direction);
+            let across = (offset - direction * along).length();
+            (along > 1.).then_some((id, 2.0f32.mul_add(across, along)))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(id, _)| id)
+}
@@ -4,8 +4,9 @@ use bevy::{ecs::schedule::ShouldRun, prelude::*, ui::PositionType::Absolute};
 use num_format::{Locale, ToFormattedString};
 
 use crate::{
+    gb9000::GameState::LevelStarting,
     levels::{CurrentScore, LevelMarker, LevelSpawnStage, ScoringSystems},
-    run_criteria::run_if_level_started,
+    run_criteria::run_on_exit,
     ui::{
         consts::{HUD_FONT_SIZE, SCORE_COLOR},
         PRIMARY_FONT,
@@ -24,7 +25,7 @@ impl Plugin for HudPlugin {
 
         app.add_system_to_stage(
             LevelSpawnStage,
-            setup_hud.with_run_criteria(run_if_level_started),
+            setup_hud.with_run_criteria(run_on_exit(&[LevelStarting])),
         );
     }
 }
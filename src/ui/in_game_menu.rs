@@ -4,8 +4,14 @@ use bevy::{
 };
 
 use crate::{
+    actions::{Action, ActionSystems, Actions},
+    gb9000::{
+        GameState::{LevelStarting, Paused, Playing},
+        GroceryBagger9000,
+    },
     levels::{LevelFinished, LevelMarker, LevelSpawnStage},
-    run_criteria::run_if_level_started,
+    nominos::{RotatePiece, UndoPlacement},
+    run_criteria::{run_if_playing, run_on_exit},
     ui::{
         consts::{BUTTON_COLOR, IN_GAME_MENU_FONT_SIZE, NORMAL_BUTTON},
         PRIMARY_FONT,
@@ -18,18 +24,51 @@ impl Plugin for InGameMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_to_stage(
             LevelSpawnStage,
-            setup_menu.with_run_criteria(run_if_level_started),
+            setup_menu.with_run_criteria(run_on_exit(&[LevelStarting])),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_restart_level_click
+                .with_run_criteria(run_if_playing)
+                .after(UiSystem::Focus),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            restart_level_shortcut
+                .with_run_criteria(run_if_playing)
+                .after(ActionSystems),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_undo_click.after(UiSystem::Focus),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            handle_rotate_click.after(UiSystem::Focus),
         );
         app.add_system_to_stage(
             CoreStage::PreUpdate,
-            handle_restart_level_click.after(UiSystem::Focus),
+            handle_pause_click.after(UiSystem::Focus),
         );
     }
 }
 
+/// In-game buttons have their own shortcuts, so menu navigation skips them.
+#[derive(Component)]
+pub struct InGameMenuButton;
+
 #[derive(Component)]
 struct RestartLevelButton;
 
+#[derive(Component)]
+struct UndoButton;
+
+#[derive(Component)]
+struct RotateButton;
+
+#[derive(Component)]
+struct PauseButton;
+
 fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
     let font = asset_server.load(PRIMARY_FONT);
     commands
@@ -46,34 +85,64 @@ fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
         })
         .insert(LevelMarker)
         .with_children(|parent| {
-            parent
-                .spawn_bundle(ButtonBundle {
-                    style: Style {
-                        padding: Rect {
-                            left: Val::Px(15.),
-                            right: Val::Px(15.),
-                            top: Val::Px(7.5),
-                            bottom: Val::Px(7.5),
-                        },
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
+            let button_bundle = ButtonBundle {
+                style: Style {
+                    padding: Rect {
+                        left: Val::Px(15.),
+                        right: Val::Px(15.),
+                        top: Val::Px(7.5),
+                        bottom: Val::Px(7.5),
                     },
-                    color: NORMAL_BUTTON.into(),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
                     ..default()
-                })
+                },
+                color: NORMAL_BUTTON.into(),
+                ..default()
+            };
+            let text_style = TextStyle {
+                font,
+                font_size: IN_GAME_MENU_FONT_SIZE,
+                color: BUTTON_COLOR,
+            };
+
+            parent
+                .spawn_bundle(button_bundle.clone())
+                .insert(InGameMenuButton)
                 .insert(RestartLevelButton)
                 .with_children(|parent| {
                     parent.spawn_bundle(TextBundle {
-                        text: Text::with_section(
-                            "Restart Level",
-                            TextStyle {
-                                font,
-                                font_size: IN_GAME_MENU_FONT_SIZE,
-                                color: BUTTON_COLOR,
-                            },
-                            default(),
-                        ),
+                        text: Text::with_section("Restart Level", text_style.clone(), default()),
+                        ..default()
+                    });
+                });
+            parent
+                .spawn_bundle(button_bundle.clone())
+                .insert(InGameMenuButton)
+                .insert(UndoButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section("Undo", text_style.clone(), default()),
+                        ..default()
+                    });
+                });
+            parent
+                .spawn_bundle(button_bundle.clone())
+                .insert(InGameMenuButton)
+                .insert(RotateButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section("Rotate", text_style.clone(), default()),
+                        ..default()
+                    });
+                });
+            parent
+                .spawn_bundle(button_bundle)
+                .insert(InGameMenuButton)
+                .insert(PauseButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section("Pause", text_style, default()),
                         ..default()
                     });
                 });
@@ -81,10 +150,53 @@ fn setup_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn handle_restart_level_click(
+    mut gb9000: ResMut<GroceryBagger9000>,
     mut level_finished: EventWriter<LevelFinished>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<RestartLevelButton>)>,
 ) {
     if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
+        gb9000.state = LevelStarting;
+        level_finished.send(LevelFinished);
+    }
+}
+
+fn restart_level_shortcut(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    actions: Res<Actions>,
+    mut level_finished: EventWriter<LevelFinished>,
+) {
+    if actions.just_pressed(Action::Restart) {
+        gb9000.state = LevelStarting;
         level_finished.send(LevelFinished);
     }
 }
+
+fn handle_undo_click(
+    mut undo_placement: EventWriter<UndoPlacement>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<UndoButton>)>,
+) {
+    if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
+        undo_placement.send(UndoPlacement);
+    }
+}
+
+fn handle_rotate_click(
+    mut rotate_piece: EventWriter<RotatePiece>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RotateButton>)>,
+) {
+    if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
+        rotate_piece.send(RotatePiece);
+    }
+}
+
+fn handle_pause_click(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PauseButton>)>,
+) {
+    if let Ok(interaction) = interaction_query.get_single()
+        && *interaction == Interaction::Clicked
+        && gb9000.state == Playing
+    {
+        gb9000.state = Paused;
+    }
+}
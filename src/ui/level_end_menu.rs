@@ -1,33 +1,41 @@
-use bevy::{app::Plugin, ecs::schedule::ShouldRun, prelude::*, ui::PositionType::Absolute};
+use bevy::{app::Plugin, prelude::*, ui::PositionType::Absolute};
 use num_format::{Locale, ToFormattedString};
 
 use crate::{
     animations,
     animations::GameSpeed,
+    conveyor_belt::BeltSeed,
     gb9000::{
-        GameState::{LevelEnded, Playing},
+        GameState::{GameComplete, LevelEnded, LevelSelect, LevelStarting},
         GroceryBagger9000,
     },
     levels::{
-        CurrentScore, LevelFinished, LevelStarted, LevelTransitionSystems, ScoringSystems,
-        LAST_LEVEL,
+        CurrentScore, LevelDefinition, LevelHandles, LevelResult, LevelStarted,
+        LevelTransitionSystems, ScoringSystems,
     },
+    run_criteria::run_on_enter,
     ui::{
         consts::{
-            BUTTON_COLOR, MENU_FONT_SIZE, NORMAL_BUTTON, SCORE_COLOR, TITLE_COLOR, TITLE_FONT_SIZE,
+            BUTTON_COLOR, EARNED_STAR_COLOR, MENU_FONT_SIZE, MISSING_STAR_COLOR, NORMAL_BUTTON,
+            SCORE_COLOR, TITLE_COLOR, TITLE_FONT_SIZE,
         },
         PRIMARY_FONT,
     },
-    App,
 };
 
 pub struct LevelEndMenuPlugin;
 
+/// Stars are drawn out of blocks, like the pieces that earn them.
+const STAR_SHAPE: [&str; 7] = [
+    "   #   ", "  ###  ", "#######", " ##### ", "  ###  ", " ## ## ", " #   # ",
+];
+const STAR_BLOCK_SIZE: f32 = 8.;
+
 impl Plugin for LevelEndMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_system(
             show_level_end_screen
-                .with_run_criteria(run_if_should_show_end_screen)
+                .with_run_criteria(run_on_enter(&[LevelEnded, GameComplete]))
                 .after(LevelTransitionSystems)
                 .after(ScoringSystems),
         );
@@ -44,6 +52,11 @@ impl Plugin for LevelEndMenuPlugin {
                 .after(handle_restart_level_click)
                 .before(LevelTransitionSystems),
         );
+        app.add_system(
+            handle_level_select_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
     }
 }
 
@@ -59,24 +72,23 @@ struct RestartLevelButton;
 #[derive(Component)]
 struct NextLevelButton;
 
-fn run_if_should_show_end_screen(
-    mut level_end: EventReader<LevelFinished>,
-    gb9000: Res<GroceryBagger9000>,
-) -> ShouldRun {
-    if level_end.iter().count() > 0 && gb9000.state == LevelEnded {
-        ShouldRun::Yes
-    } else {
-        ShouldRun::No
-    }
-}
+#[derive(Component)]
+struct LevelSelectButton;
 
 fn show_level_end_screen(
     mut commands: Commands,
     score: Res<CurrentScore>,
+    result: Res<LevelResult>,
     game_speed: Res<GameSpeed>,
     gb9000: Res<GroceryBagger9000>,
+    belt_seed: Res<BeltSeed>,
+    level_handles: Res<LevelHandles>,
+    level_definitions: Res<Assets<LevelDefinition>>,
     asset_server: Res<AssetServer>,
 ) {
+    let level = level_definitions.get(level_handles.get(gb9000.current_level));
+    let seed = level.filter(|level| level.is_seeded()).map(|_| *belt_seed);
+
     let from = Rect {
         bottom: Val::Percent(100.),
         ..default()
@@ -109,8 +121,11 @@ fn show_level_end_screen(
         ))
         .with_children(|parent| {
             spawn_level_completed_summary(parent, &gb9000, font.clone());
-            spawn_score_recap(parent, &score, font.clone());
-            spawn_restart_and_next_level_buttons(parent, &gb9000, font);
+            if let Some(stars) = result.stars {
+                spawn_star_rating(parent, stars, &game_speed);
+            }
+            spawn_score_recap(parent, &score, result.possible_points, seed, font.clone());
+            spawn_menu_buttons(parent, &gb9000, font);
         });
 }
 
@@ -121,7 +136,7 @@ fn spawn_level_completed_summary(
 ) {
     parent.spawn_bundle(TextBundle {
         text: Text::with_section(
-            if gb9000.current_level as usize == LAST_LEVEL + 1 {
+            if gb9000.state == GameComplete {
                 "Game complete!".to_string()
             } else {
                 format!("Level {} complete!", gb9000.current_level)
@@ -144,14 +159,105 @@ fn spawn_level_completed_summary(
     });
 }
 
-fn spawn_score_recap(parent: &mut ChildBuilder, score: &CurrentScore, font: Handle<Font>) {
+fn spawn_star_rating(parent: &mut ChildBuilder, stars: u8, game_speed: &GameSpeed) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                margin: Rect {
+                    bottom: Val::Px(20.),
+                    ..default()
+                },
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            for index in 0..3 {
+                let earned = index < stars;
+                let star_size = STAR_BLOCK_SIZE * 7.;
+                let mut star = parent.spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(star_size), Val::Px(star_size)),
+                        margin: Rect::all(Val::Px(5.)),
+                        ..default()
+                    },
+                    color: Color::NONE.into(),
+                    // Earned stars pop in once the menu is up
+                    transform: Transform::from_scale(if earned { Vec3::ZERO } else { Vec3::ONE }),
+                    ..default()
+                });
+                if earned {
+                    star.insert(animations::star_earned(index, game_speed));
+                }
+
+                star.with_children(|parent| {
+                    spawn_star_blocks(
+                        parent,
+                        if earned {
+                            EARNED_STAR_COLOR
+                        } else {
+                            MISSING_STAR_COLOR
+                        },
+                    );
+                });
+            }
+        });
+}
+
+fn spawn_star_blocks(parent: &mut ChildBuilder, color: Color) {
+    for (row, line) in (0u8..).zip(STAR_SHAPE) {
+        for (col, block) in (0u8..).zip(line.chars()) {
+            if block != '#' {
+                continue;
+            }
+
+            parent.spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Px(STAR_BLOCK_SIZE), Val::Px(STAR_BLOCK_SIZE)),
+                    position_type: Absolute,
+                    // UI layout runs bottom to top, so this is measured from
+                    // the top of the star
+                    position: Rect {
+                        left: Val::Px(f32::from(col) * STAR_BLOCK_SIZE),
+                        bottom: Val::Px(f32::from(row) * STAR_BLOCK_SIZE),
+                        ..default()
+                    },
+                    ..default()
+                },
+                color: color.into(),
+                ..default()
+            });
+        }
+    }
+}
+
+fn spawn_score_recap(
+    parent: &mut ChildBuilder,
+    score: &CurrentScore,
+    possible_points: Option<usize>,
+    seed: Option<BeltSeed>,
+    font: Handle<Font>,
+) {
+    let mut recap = format!("Score: {}", score.points.to_formatted_string(&Locale::en));
+    if let Some(possible_points) = possible_points {
+        recap.push_str(&format!(
+            " of a possible {}",
+            possible_points.to_formatted_string(&Locale::en)
+        ));
+    }
+    recap.push_str(&format!(
+        "\nAll time score: {}",
+        score.all_time_points.to_formatted_string(&Locale::en)
+    ));
+    if let Some(seed) = seed {
+        recap.push_str(&format!("\nSeed: {}", *seed));
+    }
+
     parent.spawn_bundle(TextBundle {
         text: Text::with_section(
-            format!(
-                "Score: {}\nAll time score: {}",
-                score.points.to_formatted_string(&Locale::en),
-                score.all_time_points.to_formatted_string(&Locale::en)
-            ),
+            recap,
             TextStyle {
                 font,
                 font_size: MENU_FONT_SIZE,
@@ -173,11 +279,7 @@ fn spawn_score_recap(parent: &mut ChildBuilder, score: &CurrentScore, font: Hand
     });
 }
 
-fn spawn_restart_and_next_level_buttons(
-    parent: &mut ChildBuilder,
-    gb9000: &GroceryBagger9000,
-    font: Handle<Font>,
-) {
+fn spawn_menu_buttons(parent: &mut ChildBuilder, gb9000: &GroceryBagger9000, font: Handle<Font>) {
     let button_bundle = ButtonBundle {
         style: Style {
             padding: Rect {
@@ -227,13 +329,31 @@ fn spawn_restart_and_next_level_buttons(
                     });
                 });
 
+            parent
+                .spawn_bundle(button_bundle.clone())
+                .insert(LevelSelectButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            "Levels",
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: MENU_FONT_SIZE,
+                                color: BUTTON_COLOR,
+                            },
+                            default(),
+                        ),
+                        ..default()
+                    });
+                });
+
             parent
                 .spawn_bundle(button_bundle)
                 .insert(NextLevelButton)
                 .with_children(|parent| {
                     parent.spawn_bundle(TextBundle {
                         text: Text::with_section(
-                            if gb9000.current_level as usize == LAST_LEVEL + 1 {
+                            if gb9000.state == GameComplete {
                                 "Start infinite mode"
                             } else {
                                 "Next Level"
@@ -253,11 +373,13 @@ fn spawn_restart_and_next_level_buttons(
 
 fn handle_next_level_click(
     mut gb9000: ResMut<GroceryBagger9000>,
+    mut belt_seed: ResMut<BeltSeed>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<NextLevelButton>)>,
 ) {
     if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
-        gb9000.state = Playing;
+        gb9000.state = LevelStarting;
         gb9000.current_level += 1;
+        belt_seed.reroll();
     }
 }
 
@@ -266,7 +388,34 @@ fn handle_restart_level_click(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<RestartLevelButton>)>,
 ) {
     if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
-        gb9000.state = Playing;
+        gb9000.state = LevelStarting;
+    }
+}
+
+fn handle_level_select_click(
+    mut commands: Commands,
+    mut gb9000: ResMut<GroceryBagger9000>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<LevelSelectButton>)>,
+    menus: Query<Entity, With<MenuMarker>>,
+    game_speed: Res<GameSpeed>,
+) {
+    if let Ok(interaction) = interaction_query.get_single() && *interaction == Interaction::Clicked {
+        gb9000.state = LevelSelect;
+
+        for menu in menus.iter() {
+            let animator = animations::level_complete_menu_ui_exit(
+                Rect {
+                    top: Val::Percent(0.),
+                    ..default()
+                },
+                Rect {
+                    top: Val::Percent(100.),
+                    ..default()
+                },
+                &game_speed,
+            );
+            commands.entity(menu).insert(animator);
+        }
     }
 }
 
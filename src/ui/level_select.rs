@@ -0,0 +1,258 @@
+use bevy::{prelude::*, ui::PositionType::Absolute};
+use num_format::{Locale, ToFormattedString};
+
+use crate::{
+    actions::{Action, Actions},
+    conveyor_belt::BeltSeed,
+    gb9000::{
+        GameState::{LevelSelect, LevelStarting, MainMenu},
+        GroceryBagger9000,
+    },
+    levels::{LevelTransitionSystems, LEVELS},
+    run_criteria::{run_on_enter, run_on_exit},
+    save::SaveData,
+    ui::{
+        consts::{
+            BUTTON_COLOR, LEVEL_SELECT_FONT_SIZE, LOCKED_BUTTON, MENU_FONT_SIZE, NORMAL_BUTTON,
+            SCORE_COLOR, TITLE_COLOR, TITLE_FONT_SIZE,
+        },
+        MenuButtonClickedSystems, PRIMARY_FONT,
+    },
+};
+
+pub struct LevelSelectPlugin;
+
+const LEVELS_PER_ROW: usize = 6;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(show_level_select.with_run_criteria(run_on_enter(&[LevelSelect])));
+        app.add_system(despawn_level_select.with_run_criteria(run_on_exit(&[LevelSelect])));
+        app.add_system(
+            handle_level_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+        app.add_system(
+            handle_back
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+    }
+}
+
+#[derive(Component)]
+struct LevelSelectMarker;
+
+#[derive(Component, Deref)]
+struct LevelButton(u16);
+
+#[derive(Component)]
+struct BackButton;
+
+fn show_level_select(
+    mut commands: Commands,
+    save: Option<Res<SaveData>>,
+    asset_server: Res<AssetServer>,
+) {
+    let default_save = SaveData::default();
+    let save = save.as_deref().unwrap_or(&default_save);
+
+    let font = asset_server.load(PRIMARY_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(LevelSelectMarker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Select a level",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: TITLE_FONT_SIZE,
+                        color: TITLE_COLOR,
+                    },
+                    default(),
+                ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(20.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            });
+
+            // The endless level comes after the last one
+            let levels = (1..).take(LEVELS.len() + 1).collect::<Vec<u16>>();
+            for row in levels.chunks(LEVELS_PER_ROW) {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        },
+                        color: Color::NONE.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for &level in row {
+                            spawn_level_button(parent, level, save, font.clone());
+                        }
+                    });
+            }
+
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(240.), Val::Auto),
+                        padding: Rect {
+                            top: Val::Px(15.),
+                            bottom: Val::Px(15.),
+                            ..default()
+                        },
+                        margin: Rect {
+                            top: Val::Px(20.),
+                            ..default()
+                        },
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    color: NORMAL_BUTTON.into(),
+                    ..default()
+                })
+                .insert(BackButton)
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            "Back",
+                            TextStyle {
+                                font,
+                                font_size: MENU_FONT_SIZE,
+                                color: BUTTON_COLOR,
+                            },
+                            default(),
+                        ),
+                        ..default()
+                    });
+                });
+        });
+}
+
+fn despawn_level_select(mut commands: Commands, menus: Query<Entity, With<LevelSelectMarker>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn spawn_level_button(parent: &mut ChildBuilder, level: u16, save: &SaveData, font: Handle<Font>) {
+    let unlocked = level <= save.unlocked_level;
+    let style = Style {
+        size: Size::new(Val::Px(120.), Val::Px(80.)),
+        margin: Rect::all(Val::Px(5.)),
+        flex_direction: FlexDirection::ColumnReverse,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let mut button = if unlocked {
+        let mut button = parent.spawn_bundle(ButtonBundle {
+            style,
+            color: NORMAL_BUTTON.into(),
+            ..default()
+        });
+        button.insert(LevelButton(level));
+        button
+    } else {
+        parent.spawn_bundle(NodeBundle {
+            style,
+            color: LOCKED_BUTTON.into(),
+            ..default()
+        })
+    };
+
+    let details = if !unlocked {
+        "Locked".to_string()
+    } else if let Some(record) = save.levels.get(&level) {
+        format!(
+            "Best: {}",
+            record.best_score.to_formatted_string(&Locale::en)
+        )
+    } else {
+        String::new()
+    };
+
+    button.with_children(|parent| {
+        parent.spawn_bundle(TextBundle {
+            text: Text::with_section(
+                if usize::from(level) > LEVELS.len() {
+                    "Endless".to_string()
+                } else {
+                    level.to_string()
+                },
+                TextStyle {
+                    font: font.clone(),
+                    font_size: MENU_FONT_SIZE,
+                    color: BUTTON_COLOR,
+                },
+                default(),
+            ),
+            ..default()
+        });
+        parent.spawn_bundle(TextBundle {
+            text: Text::with_section(
+                details,
+                TextStyle {
+                    font,
+                    font_size: LEVEL_SELECT_FONT_SIZE,
+                    color: if unlocked { SCORE_COLOR } else { BUTTON_COLOR },
+                },
+                default(),
+            ),
+            ..default()
+        });
+    });
+}
+
+fn handle_level_click(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    mut belt_seed: ResMut<BeltSeed>,
+    interaction_query: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+) {
+    for (interaction, level) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *gb9000 = GroceryBagger9000 {
+                state: LevelStarting,
+                current_level: **level,
+            };
+            belt_seed.reroll();
+        }
+    }
+}
+
+fn handle_back(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    actions: Res<Actions>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+) {
+    let clicked = interaction_query
+        .get_single()
+        .map_or(false, |interaction| *interaction == Interaction::Clicked);
+
+    if gb9000.state == LevelSelect && (clicked || actions.just_pressed(Action::Back)) {
+        gb9000.state = MainMenu;
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::{prelude::*, ui::PositionType::Absolute};
+
+use crate::{
+    gb9000::{
+        GameState::{LevelSelect, LevelStarting, MainMenu, Settings},
+        GroceryBagger9000,
+    },
+    levels::LevelTransitionSystems,
+    run_criteria::{run_on_enter, run_on_exit},
+    ui::{
+        consts::{BUTTON_COLOR, MENU_FONT_SIZE, NORMAL_BUTTON, TITLE_COLOR, TITLE_FONT_SIZE},
+        MenuButtonClickedSystems, PRIMARY_FONT,
+    },
+};
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(show_main_menu.with_run_criteria(run_on_enter(&[MainMenu])));
+        app.add_system(despawn_main_menu.with_run_criteria(run_on_exit(&[MainMenu])));
+        app.add_system(
+            handle_main_menu_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+    }
+}
+
+#[derive(Component)]
+struct MainMenuMarker;
+
+#[derive(Component)]
+enum MainMenuButton {
+    Play,
+    LevelSelect,
+    Settings,
+}
+
+fn show_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(PRIMARY_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(MainMenuMarker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Grocery Bagger 9000",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: TITLE_FONT_SIZE,
+                        color: TITLE_COLOR,
+                    },
+                    default(),
+                ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(40.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            });
+
+            for (button, text) in [
+                (MainMenuButton::Play, "Play"),
+                (MainMenuButton::LevelSelect, "Levels"),
+                (MainMenuButton::Settings, "Settings"),
+            ] {
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(240.), Val::Auto),
+                            padding: Rect {
+                                top: Val::Px(15.),
+                                bottom: Val::Px(15.),
+                                ..default()
+                            },
+                            margin: Rect {
+                                bottom: Val::Px(10.),
+                                ..default()
+                            },
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        color: NORMAL_BUTTON.into(),
+                        ..default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                text,
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: MENU_FONT_SIZE,
+                                    color: BUTTON_COLOR,
+                                },
+                                default(),
+                            ),
+                            ..default()
+                        });
+                    });
+            }
+        });
+}
+
+fn despawn_main_menu(mut commands: Commands, menus: Query<Entity, With<MainMenuMarker>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn handle_main_menu_click(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    interaction_query: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        // Play picks up from the furthest level reached
+        *gb9000 = GroceryBagger9000 {
+            state: match button {
+                MainMenuButton::Play => LevelStarting,
+                MainMenuButton::LevelSelect => LevelSelect,
+                MainMenuButton::Settings => Settings,
+            },
+            ..*gb9000
+        };
+    }
+}
@@ -2,17 +2,27 @@ use bevy::prelude::*;
 
 pub use consts::PRIMARY_FONT;
 use consts::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+use gamepad_navigation::GamepadNavigationPlugin;
 use hud::HudPlugin;
 use in_game_menu::InGameMenuPlugin;
 use juice::JuicePlugin;
 use level_end_menu::LevelEndMenuPlugin;
 pub use level_end_menu::MenuButtonClickedSystems;
+use level_select::LevelSelectPlugin;
+use main_menu::MainMenuPlugin;
+use pause_menu::PauseMenuPlugin;
+use settings::SettingsPlugin;
 
 mod consts;
+mod gamepad_navigation;
 mod hud;
 mod in_game_menu;
 mod juice;
 mod level_end_menu;
+mod level_select;
+mod main_menu;
+mod pause_menu;
+mod settings;
 
 pub struct UiPlugin;
 
@@ -21,7 +31,12 @@ impl Plugin for UiPlugin {
         app.add_plugin(HudPlugin);
         app.add_plugin(LevelEndMenuPlugin);
         app.add_plugin(InGameMenuPlugin);
+        app.add_plugin(MainMenuPlugin);
+        app.add_plugin(LevelSelectPlugin);
+        app.add_plugin(PauseMenuPlugin);
+        app.add_plugin(SettingsPlugin);
         app.add_plugin(JuicePlugin);
+        app.add_plugin(GamepadNavigationPlugin);
 
         app.add_system(button_hover_system);
     }
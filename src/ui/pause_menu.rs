@@ -0,0 +1,183 @@
+use bevy::{prelude::*, ui::PositionType::Absolute};
+
+use crate::{
+    actions::{Action, Actions},
+    gb9000::{
+        GameState::{LevelSelect, LevelStarting, Paused, Playing, Settings},
+        GroceryBagger9000,
+    },
+    levels::{LevelFinished, LevelTransitionSystems},
+    run_criteria::{run_on_enter, run_on_exit},
+    ui::{
+        consts::{
+            BUTTON_COLOR, MENU_FONT_SIZE, NORMAL_BUTTON, PAUSE_OVERLAY_COLOR, TITLE_COLOR,
+            TITLE_FONT_SIZE,
+        },
+        MenuButtonClickedSystems, PRIMARY_FONT,
+    },
+};
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(show_pause_menu.with_run_criteria(run_on_enter(&[Paused])));
+        app.add_system(despawn_pause_menu.with_run_criteria(run_on_exit(&[Paused])));
+
+        // Resuming in the update stage keeps the click that did it from also
+        // picking up a piece
+        app.add_system(
+            toggle_pause
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+        app.add_system(
+            handle_pause_menu_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+    }
+}
+
+#[derive(Component)]
+struct PauseMenuMarker;
+
+#[derive(Component)]
+enum PauseMenuButton {
+    Resume,
+    Restart,
+    LevelSelect,
+    Settings,
+    #[cfg(not(target_arch = "wasm32"))]
+    Quit,
+}
+
+fn show_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(PRIMARY_FONT);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: PAUSE_OVERLAY_COLOR.into(),
+            ..default()
+        })
+        .insert(PauseMenuMarker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Paused",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: TITLE_FONT_SIZE,
+                        color: TITLE_COLOR,
+                    },
+                    default(),
+                ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(40.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            });
+
+            for (button, text) in [
+                (PauseMenuButton::Resume, "Resume"),
+                (PauseMenuButton::Restart, "Restart Level"),
+                (PauseMenuButton::LevelSelect, "Levels"),
+                (PauseMenuButton::Settings, "Settings"),
+                #[cfg(not(target_arch = "wasm32"))]
+                (PauseMenuButton::Quit, "Quit"),
+            ] {
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(240.), Val::Auto),
+                            padding: Rect {
+                                top: Val::Px(15.),
+                                bottom: Val::Px(15.),
+                                ..default()
+                            },
+                            margin: Rect {
+                                bottom: Val::Px(10.),
+                                ..default()
+                            },
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        color: NORMAL_BUTTON.into(),
+                        ..default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                text,
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: MENU_FONT_SIZE,
+                                    color: BUTTON_COLOR,
+                                },
+                                default(),
+                            ),
+                            ..default()
+                        });
+                    });
+            }
+        });
+}
+
+fn despawn_pause_menu(mut commands: Commands, menus: Query<Entity, With<PauseMenuMarker>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+pub fn toggle_pause(mut gb9000: ResMut<GroceryBagger9000>, actions: Res<Actions>) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match gb9000.state {
+        Playing => gb9000.state = Paused,
+        Paused => gb9000.state = Playing,
+        _ => {}
+    }
+}
+
+fn handle_pause_menu_click(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    mut level_finished: EventWriter<LevelFinished>,
+    #[cfg(not(target_arch = "wasm32"))] mut app_exit: EventWriter<bevy::app::AppExit>,
+    interaction_query: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button {
+            PauseMenuButton::Resume => gb9000.state = Playing,
+            PauseMenuButton::Restart => {
+                gb9000.state = LevelStarting;
+                level_finished.send(LevelFinished);
+            }
+            PauseMenuButton::LevelSelect => {
+                gb9000.state = LevelSelect;
+                level_finished.send(LevelFinished);
+            }
+            PauseMenuButton::Settings => gb9000.state = Settings,
+            #[cfg(not(target_arch = "wasm32"))]
+            PauseMenuButton::Quit => app_exit.send(bevy::app::AppExit),
+        }
+    }
+}
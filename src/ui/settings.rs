@@ -0,0 +1,663 @@
+use bevy::{prelude::*, ui::PositionType::Absolute};
+
+use crate::{
+    actions::{Action, Actions, Binding, Bindings},
+    animations::GameSpeed,
+    conveyor_belt::{ConveyorBeltOptions, MAX_NUM_PIECES},
+    gb9000::{
+        GameState::{self, MainMenu, Settings},
+        GroceryBagger9000,
+    },
+    levels::LevelTransitionSystems,
+    robot::RobotOptions,
+    run_criteria::{run_on_enter, run_on_exit},
+    ui::{
+        consts::{
+            BUTTON_COLOR, CONTROLS_FONT_SIZE, MENU_FONT_SIZE, NORMAL_BUTTON, PAUSE_OVERLAY_COLOR,
+            TITLE_COLOR, TITLE_FONT_SIZE,
+        },
+        pause_menu::toggle_pause,
+        MenuButtonClickedSystems, PRIMARY_FONT,
+    },
+};
+
+pub struct SettingsPlugin;
+
+const GAME_SPEEDS: [f32; 5] = [0.5, 0.75, 1., 1.5, 2.];
+const KEY_BINDING_SLOTS: usize = 3;
+const GAMEPAD_BINDING_SLOTS: usize = 2;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(show_settings.with_run_criteria(run_on_enter(&[Settings])));
+        app.add_system(despawn_settings.with_run_criteria(run_on_exit(&[Settings])));
+        app.add_system(
+            handle_setting_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+        app.add_system(
+            handle_back
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems)
+                .after(capture_binding)
+                // Escape would otherwise unpause the menu it just went back to
+                .after(toggle_pause),
+        );
+        app.add_system(update_setting_values.after(handle_setting_click));
+
+        app.init_resource::<Rebinding>();
+        app.add_system(capture_binding.before(handle_binding_click));
+        app.add_system(
+            handle_binding_click
+                .label(MenuButtonClickedSystems)
+                .before(LevelTransitionSystems),
+        );
+        app.add_system(
+            update_binding_values
+                .after(capture_binding)
+                .after(handle_binding_click),
+        );
+    }
+}
+
+#[derive(Component)]
+struct SettingsMarker;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Setting {
+    GameSpeed,
+    Robot,
+    SelectablePieces,
+}
+
+/// Steps a setting up or down.
+#[derive(Component)]
+struct SettingButton {
+    setting: Setting,
+    step: i8,
+}
+
+#[derive(Component, Deref)]
+struct SettingValue(Setting);
+
+#[derive(Component)]
+struct BackButton;
+
+/// Edits one of the keyboard and mouse bindings of an action or one of its
+/// gamepad ones. Slots past the last binding add a new one.
+#[derive(Component, Debug, Copy, Clone, Eq, PartialEq)]
+struct BindingButton {
+    action: Action,
+    gamepad: bool,
+    slot: usize,
+}
+
+#[derive(Component, Deref)]
+struct BindingValue(BindingButton);
+
+/// The binding waiting for the player to press its new input.
+#[derive(Default)]
+struct Rebinding {
+    listening: Option<BindingButton>,
+    /// Set on the frame an input was captured so the gamepad button that did
+    /// it doesn't also start listening again.
+    captured: bool,
+    /// The action that would have lost its last binding to the input pressed
+    /// while listening.
+    needed_by: Option<Action>,
+}
+
+impl Setting {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::GameSpeed => "Game speed",
+            Self::Robot => "Robot",
+            Self::SelectablePieces => "Selectable pieces",
+        }
+    }
+
+    fn value(
+        self,
+        game_speed: &GameSpeed,
+        robot_options: &RobotOptions,
+        belt_options: &ConveyorBeltOptions,
+    ) -> String {
+        match self {
+            Self::GameSpeed => format!("{}x", **game_speed),
+            Self::Robot => if robot_options.enabled { "On" } else { "Off" }.to_string(),
+            Self::SelectablePieces => belt_options.num_pieces_selectable.to_string(),
+        }
+    }
+}
+
+fn show_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_speed: Res<GameSpeed>,
+    robot_options: Res<RobotOptions>,
+    belt_options: Res<ConveyorBeltOptions>,
+    bindings: Res<Bindings>,
+) {
+    let font = asset_server.load(PRIMARY_FONT);
+    let text_style = TextStyle {
+        font,
+        font_size: MENU_FONT_SIZE,
+        color: BUTTON_COLOR,
+    };
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: PAUSE_OVERLAY_COLOR.into(),
+            ..default()
+        })
+        .insert(SettingsMarker)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    "Settings",
+                    TextStyle {
+                        font_size: TITLE_FONT_SIZE,
+                        color: TITLE_COLOR,
+                        ..text_style.clone()
+                    },
+                    default(),
+                ),
+                style: Style {
+                    margin: Rect {
+                        bottom: Val::Px(40.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            });
+
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::FlexStart,
+                        ..default()
+                    },
+                    color: Color::NONE.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(column_bundle())
+                        .with_children(|parent| {
+                            for setting in [
+                                Setting::GameSpeed,
+                                Setting::Robot,
+                                Setting::SelectablePieces,
+                            ] {
+                                let value =
+                                    setting.value(&game_speed, &robot_options, &belt_options);
+                                spawn_setting_row(parent, setting, value, &text_style);
+                            }
+
+                            spawn_back_button(parent, &text_style);
+                        });
+
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                margin: Rect {
+                                    left: Val::Px(40.),
+                                    ..default()
+                                },
+                                ..column_bundle().style
+                            },
+                            ..column_bundle()
+                        })
+                        .with_children(|parent| {
+                            let text_style = TextStyle {
+                                font_size: CONTROLS_FONT_SIZE,
+                                ..text_style.clone()
+                            };
+                            for action in Action::all() {
+                                if action == Action::DebugMenu && !cfg!(feature = "debug") {
+                                    continue;
+                                }
+                                spawn_binding_row(parent, action, &bindings, &text_style);
+                            }
+                        });
+                });
+        });
+}
+
+fn column_bundle() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        color: Color::NONE.into(),
+        ..default()
+    }
+}
+
+fn spawn_back_button(parent: &mut ChildBuilder, text_style: &TextStyle) {
+    parent
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(240.), Val::Auto),
+                padding: Rect {
+                    top: Val::Px(15.),
+                    bottom: Val::Px(15.),
+                    ..default()
+                },
+                margin: Rect {
+                    top: Val::Px(40.),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: NORMAL_BUTTON.into(),
+            ..default()
+        })
+        .insert(BackButton)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section("Back", text_style.clone(), default()),
+                ..default()
+            });
+        });
+}
+
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    setting: Setting,
+    value: String,
+    text_style: &TextStyle,
+) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(setting.name(), text_style.clone(), default()),
+                style: Style {
+                    size: Size::new(Val::Px(300.), Val::Auto),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // The robot is toggled by clicking its value
+            let stepped = setting != Setting::Robot;
+            if stepped {
+                spawn_step_button(parent, setting, -1, text_style);
+            }
+
+            let mut value_box = if stepped {
+                parent.spawn_bundle(NodeBundle {
+                    style: setting_button_style(120.),
+                    color: Color::NONE.into(),
+                    ..default()
+                })
+            } else {
+                let mut button = parent.spawn_bundle(ButtonBundle {
+                    style: setting_button_style(120.),
+                    color: NORMAL_BUTTON.into(),
+                    ..default()
+                });
+                button.insert(SettingButton { setting, step: 1 });
+                button
+            };
+            value_box.with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(value, text_style.clone(), default()),
+                        ..default()
+                    })
+                    .insert(SettingValue(setting));
+            });
+
+            if stepped {
+                spawn_step_button(parent, setting, 1, text_style);
+            }
+        });
+}
+
+fn spawn_step_button(
+    parent: &mut ChildBuilder,
+    setting: Setting,
+    step: i8,
+    text_style: &TextStyle,
+) {
+    parent
+        .spawn_bundle(ButtonBundle {
+            style: setting_button_style(50.),
+            color: NORMAL_BUTTON.into(),
+            ..default()
+        })
+        .insert(SettingButton { setting, step })
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    if step < 0 { "-" } else { "+" },
+                    text_style.clone(),
+                    default(),
+                ),
+                ..default()
+            });
+        });
+}
+
+fn spawn_binding_row(
+    parent: &mut ChildBuilder,
+    action: Action,
+    bindings: &Bindings,
+    text_style: &TextStyle,
+) {
+    parent
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(action.name(), text_style.clone(), default()),
+                style: Style {
+                    size: Size::new(Val::Px(150.), Val::Auto),
+                    ..default()
+                },
+                ..default()
+            });
+
+            let slots = (0..KEY_BINDING_SLOTS)
+                .map(|slot| (false, slot))
+                .chain((0..GAMEPAD_BINDING_SLOTS).map(|slot| (true, slot)));
+            for (gamepad, slot) in slots {
+                let button = BindingButton {
+                    action,
+                    gamepad,
+                    slot,
+                };
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(110.), Val::Px(26.)),
+                            margin: Rect::all(Val::Px(2.)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        color: NORMAL_BUTTON.into(),
+                        ..default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(TextBundle {
+                                text: Text::with_section(
+                                    binding_name(bindings, button),
+                                    text_style.clone(),
+                                    default(),
+                                ),
+                                ..default()
+                            })
+                            .insert(BindingValue(button));
+                    });
+            }
+        });
+}
+
+fn binding_name(bindings: &Bindings, button: BindingButton) -> String {
+    bindings
+        .of_kind(button.action, button.gamepad)
+        .nth(button.slot)
+        .map_or_else(|| "-".to_string(), Binding::name)
+}
+
+fn setting_button_style(width: f32) -> Style {
+    Style {
+        size: Size::new(Val::Px(width), Val::Px(50.)),
+        margin: Rect::all(Val::Px(5.)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+fn despawn_settings(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    menus: Query<Entity, With<SettingsMarker>>,
+) {
+    rebinding.listening = None;
+    rebinding.needed_by = None;
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn handle_setting_click(
+    mut game_speed: ResMut<GameSpeed>,
+    mut robot_options: ResMut<RobotOptions>,
+    mut belt_options: ResMut<ConveyorBeltOptions>,
+    interaction_query: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button.setting {
+            Setting::GameSpeed => {
+                if let Some(next) = step_game_speed(**game_speed, button.step) {
+                    **game_speed = next;
+                }
+            }
+            Setting::Robot => robot_options.enabled = !robot_options.enabled,
+            Setting::SelectablePieces => {
+                let current = belt_options.num_pieces_selectable;
+                let next = if button.step < 0 {
+                    current.saturating_sub(1).max(1)
+                } else {
+                    (current + 1).min(MAX_NUM_PIECES)
+                };
+                if next != belt_options.num_pieces_selectable {
+                    belt_options.num_pieces_selectable = next;
+                }
+            }
+        }
+    }
+}
+
+/// The next preset speed in the step's direction, if there is one. Speeds set
+/// from the debug menu step to the neighboring presets.
+fn step_game_speed(speed: f32, step: i8) -> Option<f32> {
+    let next = if step < 0 {
+        GAME_SPEEDS.iter().rev().find(|&&preset| preset < speed)
+    } else {
+        GAME_SPEEDS.iter().find(|&&preset| preset > speed)
+    };
+    next.copied()
+}
+
+fn update_setting_values(
+    game_speed: Res<GameSpeed>,
+    robot_options: Res<RobotOptions>,
+    belt_options: Res<ConveyorBeltOptions>,
+    mut values: Query<(&mut Text, &SettingValue)>,
+) {
+    if !game_speed.is_changed() && !robot_options.is_changed() && !belt_options.is_changed() {
+        return;
+    }
+
+    for (mut text, setting) in values.iter_mut() {
+        text.sections[0].value = setting.value(&game_speed, &robot_options, &belt_options);
+    }
+}
+
+fn handle_binding_click(
+    mut rebinding: ResMut<Rebinding>,
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+) {
+    if rebinding.captured {
+        return;
+    }
+
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            rebinding.listening = Some(*button);
+            rebinding.needed_by = None;
+        }
+    }
+}
+
+/// Replaces the binding being edited with the next matching input. Escape
+/// cancels instead.
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<Bindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    ui_buttons: Query<&Interaction, With<Button>>,
+) {
+    if rebinding.captured {
+        rebinding.captured = false;
+    }
+    let Some(target) = rebinding.listening else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        rebinding.listening = None;
+        rebinding.needed_by = None;
+        rebinding.captured = true;
+        return;
+    }
+
+    let binding = if target.gamepad {
+        gamepad_buttons
+            .get_just_pressed()
+            .next()
+            .map(|GamepadButton(_, button)| Binding::Gamepad(*button))
+    } else {
+        // Clicking another button picks it instead of binding the click
+        let over_button = ui_buttons
+            .iter()
+            .any(|interaction| *interaction != Interaction::None);
+        keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|key| Binding::Key(*key))
+            .or_else(|| {
+                mouse_button_input
+                    .get_just_pressed()
+                    .next()
+                    .filter(|_| !over_button)
+                    .map(|button| Binding::Mouse(*button))
+            })
+    };
+    let Some(binding) = binding else {
+        return;
+    };
+
+    // Keep listening for another input if this one can't be taken
+    match bindings.rebind(target.action, target.slot, binding) {
+        Ok(()) => {
+            rebinding.listening = None;
+            rebinding.needed_by = None;
+        }
+        Err(action) => rebinding.needed_by = Some(action),
+    }
+    rebinding.captured = true;
+}
+
+fn update_binding_values(
+    rebinding: Res<Rebinding>,
+    bindings: Res<Bindings>,
+    mut values: Query<(&mut Text, &BindingValue)>,
+) {
+    if !rebinding.is_changed() && !bindings.is_changed() {
+        return;
+    }
+
+    for (mut text, button) in values.iter_mut() {
+        text.sections[0].value = if rebinding.listening != Some(**button) {
+            binding_name(&bindings, **button)
+        } else if let Some(action) = rebinding.needed_by {
+            format!("{} needs it", action.name())
+        } else {
+            "Press a button".to_string()
+        };
+    }
+}
+
+/// Returns to whichever menu opened the settings.
+fn handle_back(
+    mut gb9000: ResMut<GroceryBagger9000>,
+    mut opened_from: Local<Option<GameState>>,
+    actions: Res<Actions>,
+    rebinding: Res<Rebinding>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+) {
+    if gb9000.state != Settings {
+        *opened_from = Some(gb9000.state);
+        return;
+    }
+
+    let clicked = interaction_query
+        .get_single()
+        .map_or(false, |interaction| *interaction == Interaction::Clicked);
+    // Escape cancels rebinding before it leaves the settings
+    let backed_out =
+        actions.just_pressed(Action::Back) && rebinding.listening.is_none() && !rebinding.captured;
+    if clicked || backed_out {
+        gb9000.state = opened_from.unwrap_or(MainMenu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_speed_steps_through_presets() {
+        assert_eq!(step_game_speed(1., 1), Some(1.5));
+        assert_eq!(step_game_speed(1., -1), Some(0.75));
+    }
+
+    #[test]
+    fn game_speed_stops_at_the_last_presets() {
+        assert_eq!(step_game_speed(2., 1), None);
+        assert_eq!(step_game_speed(0.5, -1), None);
+    }
+
+    #[test]
+    fn custom_game_speed_steps_to_neighboring_presets() {
+        assert_eq!(step_game_speed(1.2, 1), Some(1.5));
+        assert_eq!(step_game_speed(1.2, -1), Some(1.));
+        assert_eq!(step_game_speed(5., -1), Some(2.));
+        assert_eq!(step_game_speed(0.1, 1), Some(0.5));
+    }
+}
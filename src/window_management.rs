@@ -7,7 +7,10 @@ use bevy::{
     winit::{UpdateMode, WinitSettings},
 };
 
-use crate::hex_color;
+use crate::{
+    actions::{Action, Actions},
+    hex_color,
+};
 
 const DEFAULT_WIDTH: f32 = 1200.;
 const DEFAULT_HEIGHT: f32 = 675.;
@@ -48,6 +51,19 @@ impl Plugin for WindowManager {
     }
 }
 
+/// Stands in for [`WindowManager`] when running without a window.
+pub struct HeadlessWindowManager;
+
+impl Plugin for HeadlessWindowManager {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DipsWindow {
+            width: TARGET_WIDTH_UNITS,
+            height: TARGET_HEIGHT_UNITS,
+            scale: 1. / PIXELS_PER_UNIT,
+        });
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemLabel)]
 pub struct WindowSystems;
 
@@ -115,8 +131,8 @@ fn scale_window(
     dips_window.scale = proj.scale;
 }
 
-fn full_screen_toggle(mut windows: ResMut<Windows>, keyboard_input: Res<Input<KeyCode>>) {
-    if keyboard_input.just_pressed(KeyCode::F) {
+fn full_screen_toggle(mut windows: ResMut<Windows>, actions: Res<Actions>) {
+    if actions.just_pressed(Action::ToggleFullscreen) {
         let window = windows.get_primary_mut().unwrap();
         window.set_mode(if window.mode() == WindowMode::Windowed {
             WindowMode::BorderlessFullscreen
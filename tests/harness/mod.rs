@@ -0,0 +1,373 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::AssetPlugin,
+    ecs::event::{Events, ManualEventReader},
+    input::{
+        gamepad::{GamepadEventRaw, GamepadEventType},
+        keyboard::KeyboardInput,
+        mouse::MouseButtonInput,
+        touch::{TouchInput, TouchPhase},
+        ElementState, InputPlugin,
+    },
+    prelude::*,
+    scene::ScenePlugin,
+    window::{WindowId, WindowPlugin},
+};
+use bevy_tweening::Animator;
+use grocery_bagger_9000::{
+    animations::{AnimationComponentsBundle, GameSpeed},
+    bags::{BagMarker, BagSize},
+    conveyor_belt::{BeltPieceIds, BeltSeed, ConveyorBeltMarker},
+    gb9000::{GameState, GroceryBagger9000, HeadlessGroceryBagger9000Plugin},
+    levels::{CurrentScore, LevelFinished, LevelMarker, LevelResult, LevelStarted},
+    nominos::{
+        Nomino, NominoColor, NominoMarker, PiecePlaced, PlacementPreview, Selected, UndoPlacement,
+    },
+    robot::RobotOptions,
+    window_management::{DipsWindow, HeadlessWindowManager},
+};
+
+// Headless frames only take a few milliseconds, so speed the game up until each
+// frame covers about as much time as a rendered one would. Going much faster
+// lets animations finish before the systems waiting on them get a chance to
+// run.
+const SIMULATION_SPEED: f32 = 10.;
+const TIMEOUT: Duration = Duration::from_secs(30);
+const SEED: u64 = 9000;
+
+/// Runs the game without a window so levels can be played from tests.
+pub struct Simulation {
+    app: App,
+    level_started: ManualEventReader<LevelStarted>,
+    level_finished: ManualEventReader<LevelFinished>,
+    finished: bool,
+}
+
+impl Simulation {
+    pub fn new(level: u16, robot: bool) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins);
+        app.add_plugin(TransformPlugin);
+        app.add_plugin(HierarchyPlugin);
+        app.add_plugin(InputPlugin);
+        app.add_plugin(WindowPlugin::default());
+        app.add_plugin(AssetPlugin);
+        app.add_plugin(ScenePlugin);
+        // Only needed for rapier's scene collider initialization
+        app.add_asset::<Mesh>();
+        app.add_plugin(HeadlessWindowManager);
+        app.add_plugin(HeadlessGroceryBagger9000Plugin);
+
+        app.insert_resource(RobotOptions {
+            enabled: robot,
+            ..default()
+        });
+        app.insert_resource(BeltSeed(SEED));
+        app.insert_resource(GroceryBagger9000 {
+            current_level: level,
+            ..default()
+        });
+        **app.world.resource_mut::<GameSpeed>() = SIMULATION_SPEED;
+
+        let mut simulation = Self {
+            app,
+            level_started: default(),
+            level_finished: default(),
+            finished: false,
+        };
+
+        // Boot like the game does before starting the level from the menu
+        simulation
+            .run_until(|s| s.app.world.resource::<GroceryBagger9000>().state != GameState::Boot);
+        simulation.set_state(GameState::LevelStarting);
+
+        let mut started = false;
+        simulation.run_until(|s| {
+            started |= s.level_started.iter(s.app.world.resource()).count() > 0;
+            started
+        });
+        simulation.settle();
+        simulation
+    }
+
+    pub fn update(&mut self) {
+        self.app.update();
+
+        let level_finished = self.app.world.resource::<Events<LevelFinished>>();
+        self.finished |= self.level_finished.iter(level_finished).count() > 0;
+    }
+
+    pub fn run_until(&mut self, mut done: impl FnMut(&mut Self) -> bool) {
+        let start = Instant::now();
+        while !done(self) {
+            assert!(start.elapsed() < TIMEOUT, "simulation timed out");
+            self.update();
+        }
+    }
+
+    /// Runs the game until the given amount of game time has passed.
+    pub fn advance(&mut self, duration: Duration) {
+        let mut elapsed = Duration::ZERO;
+        self.run_until(|s| {
+            let time = s.app.world.resource::<Time>();
+            elapsed += time.delta().mul_f32(**s.app.world.resource::<GameSpeed>());
+            elapsed >= duration
+        });
+    }
+
+    /// Waits for all in-flight animations, such as bag replacements, to
+    /// complete.
+    pub fn settle(&mut self) {
+        self.update();
+        self.run_until(|s| {
+            s.app
+                .world
+                .query_filtered::<(), With<Animator<Transform>>>()
+                .iter(&s.app.world)
+                .next()
+                .is_none()
+        });
+    }
+
+    pub fn belt_pieces(&mut self) -> Vec<Entity> {
+        self.app
+            .world
+            .query_filtered::<&BeltPieceIds, With<ConveyorBeltMarker>>()
+            .iter(&self.app.world)
+            .next()
+            .map(|pieces| pieces.to_vec())
+            .unwrap_or_default()
+    }
+
+    pub fn belt_nominos(&mut self) -> Vec<(Nomino, NominoColor)> {
+        let pieces = self.belt_pieces();
+        pieces
+            .into_iter()
+            .map(|piece| {
+                let piece = self.app.world.entity(piece);
+                (
+                    *piece.get::<Nomino>().unwrap(),
+                    *piece.get::<NominoColor>().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn piece_position(&self, piece: Entity) -> Vec2 {
+        self.app
+            .world
+            .get::<GlobalTransform>(piece)
+            .unwrap()
+            .translation
+            .truncate()
+    }
+
+    /// Returns the bags ordered from left to right.
+    pub fn bags(&mut self) -> Vec<(Entity, GlobalTransform, BagSize)> {
+        let mut bags = self
+            .app
+            .world
+            .query_filtered::<(Entity, &GlobalTransform, &BagSize), With<BagMarker>>()
+            .iter(&self.app.world)
+            .map(|(id, transform, size)| (id, *transform, *size))
+            .collect::<Vec<_>>();
+        bags.sort_by(|a, b| a.1.translation.x.total_cmp(&b.1.translation.x));
+        bags
+    }
+
+    /// Places the belt piece in `slot` so that its pivot block lands on the
+    /// bag cell at `position`, the same coordinates used by level files.
+    ///
+    /// Like a player would, the piece is first picked up and held over its
+    /// destination so the physics world catches up before it gets dropped.
+    pub fn place(&mut self, slot: usize, bag: usize, position: (u8, u8), rotation: Quat) {
+        self.drop_piece(slot, bag, position, rotation);
+        self.settle();
+    }
+
+    /// Places a piece like [`Self::place`] without waiting for the bags to
+    /// react.
+    pub fn drop_piece(&mut self, slot: usize, bag: usize, position: (u8, u8), rotation: Quat) {
+        let piece = self.belt_pieces()[slot];
+        let (bag, bag_transform, bag_size) = self.bags()[bag];
+        let target = bag_transform.translation - bag_size.origin()
+            + Vec3::new(f32::from(position.0) + 0.5, f32::from(position.1) + 0.5, 0.);
+
+        let mut piece_entity = self.app.world.entity_mut(piece);
+        let z = piece_entity.get::<GlobalTransform>().unwrap().translation.z;
+        piece_entity.remove_bundle::<AnimationComponentsBundle<Transform>>();
+        piece_entity.remove::<Parent>();
+        piece_entity
+            .insert(Transform::from_translation(target + Vec3::Z * z).with_rotation(rotation))
+            .insert(LevelMarker)
+            .insert(Selected);
+
+        self.update();
+        self.update();
+
+        let world = &mut self.app.world;
+        world.entity_mut(piece).remove::<Selected>();
+        world
+            .resource_mut::<Events<PiecePlaced>>()
+            .send(PiecePlaced {
+                piece,
+                bag,
+                by_robot: false,
+            });
+        self.update();
+    }
+
+    /// Taps a key, holding it down for a frame.
+    pub fn press(&mut self, key: KeyCode) {
+        for state in [ElementState::Pressed, ElementState::Released] {
+            self.app
+                .world
+                .resource_mut::<Events<KeyboardInput>>()
+                .send(KeyboardInput {
+                    scan_code: 0,
+                    key_code: Some(key),
+                    state,
+                });
+            self.update();
+        }
+    }
+
+    /// Taps a button on the first gamepad, connecting it if needed.
+    pub fn press_button(&mut self, button: GamepadButtonType) {
+        let gamepad = Gamepad(0);
+        if !self.app.world.resource::<Gamepads>().contains(&gamepad) {
+            self.app
+                .world
+                .resource_mut::<Events<GamepadEventRaw>>()
+                .send(GamepadEventRaw(gamepad, GamepadEventType::Connected));
+            self.update();
+        }
+
+        for value in [1., 0.] {
+            self.app
+                .world
+                .resource_mut::<Events<GamepadEventRaw>>()
+                .send(GamepadEventRaw(
+                    gamepad,
+                    GamepadEventType::ButtonChanged(button, value),
+                ));
+            self.update();
+        }
+    }
+
+    /// Moves the first finger to the given world position.
+    pub fn touch(&mut self, phase: TouchPhase, position: Vec2) {
+        let height = self
+            .app
+            .world
+            .resource::<Windows>()
+            .get_primary()
+            .map_or(0., Window::height);
+        let position = position / self.app.world.resource::<DipsWindow>().scale;
+        self.app
+            .world
+            .resource_mut::<Events<TouchInput>>()
+            .send(TouchInput {
+                phase,
+                position: Vec2::new(position.x, height - position.y),
+                force: None,
+                id: 0,
+            });
+        self.update();
+    }
+
+    /// Moves the mouse cursor to the given world position.
+    pub fn move_cursor(&mut self, position: Vec2) {
+        let position = position / self.app.world.resource::<DipsWindow>().scale;
+        self.app
+            .world
+            .resource_mut::<Events<CursorMoved>>()
+            .send(CursorMoved {
+                id: WindowId::primary(),
+                position,
+            });
+        self.update();
+    }
+
+    /// Presses or releases the left mouse button, which is also how browsers
+    /// report the first finger.
+    pub fn mouse(&mut self, state: ElementState) {
+        self.app
+            .world
+            .resource_mut::<Events<MouseButtonInput>>()
+            .send(MouseButtonInput {
+                button: MouseButton::Left,
+                state,
+            });
+        self.update();
+    }
+
+    /// Moves the held piece down with the keyboard for as long as it keeps
+    /// moving.
+    pub fn lower_held_piece(&mut self) {
+        let mut held = self.held_piece().unwrap().1;
+        loop {
+            self.press(KeyCode::Down);
+            let moved = self.held_piece().unwrap().1;
+            if moved == held {
+                break;
+            }
+            held = moved;
+        }
+    }
+
+    pub fn held_piece(&mut self) -> Option<(Entity, Transform)> {
+        self.app
+            .world
+            .query_filtered::<(Entity, &Transform), (With<NominoMarker>, With<Selected>)>()
+            .iter(&self.app.world)
+            .next()
+            .map(|(id, transform)| (id, *transform))
+    }
+
+    /// Whether the held piece can be placed where it's previewed, if it's over
+    /// a bag.
+    pub fn placement_preview(&mut self) -> Option<bool> {
+        self.app
+            .world
+            .query::<&PlacementPreview>()
+            .iter(&self.app.world)
+            .next()
+            .map(|preview| preview.valid)
+    }
+
+    pub fn undo(&mut self) {
+        self.app
+            .world
+            .resource_mut::<Events<UndoPlacement>>()
+            .send(UndoPlacement);
+
+        self.settle();
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app.world.resource::<GroceryBagger9000>().state
+    }
+
+    pub fn set_state(&mut self, state: GameState) {
+        self.app.world.resource_mut::<GroceryBagger9000>().state = state;
+    }
+
+    pub fn score(&self) -> &CurrentScore {
+        self.app.world.resource::<CurrentScore>()
+    }
+
+    pub fn level_result(&self) -> LevelResult {
+        *self.app.world.resource::<LevelResult>()
+    }
+
+    pub const fn level_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn finish_level(&mut self) {
+        self.run_until(|s| s.finished);
+    }
+}
@@ -0,0 +1,328 @@
+use std::time::Duration;
+
+use bevy::{
+    input::{touch::TouchPhase, ElementState},
+    prelude::*,
+};
+use grocery_bagger_9000::{gb9000::GameState, levels::LEVELS, nominos::DEG_90};
+
+use crate::harness::Simulation;
+
+mod harness;
+
+#[test]
+fn completing_first_level_fills_the_bag() {
+    let mut simulation = Simulation::new(1, false);
+
+    simulation.place(0, 0, (2, 2), Quat::IDENTITY);
+    simulation.finish_level();
+
+    assert_eq!(simulation.score().points, 8000);
+    // The result is recorded once the level end screen comes up
+    simulation.update();
+    assert_eq!(simulation.level_result().stars, Some(3));
+}
+
+#[test]
+fn perfectly_packed_bag_gets_max_score() {
+    let mut simulation = Simulation::new(2, false);
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    simulation.place(0, 0, (0, 2), Quat::IDENTITY);
+    assert!(!simulation.level_finished());
+    simulation.place(0, 0, (2, 2), Quat::IDENTITY);
+    simulation.finish_level();
+
+    assert_eq!(simulation.score().points, 8000);
+    assert_eq!(simulation.score().packed_points, 8000);
+}
+
+#[test]
+fn partially_filled_bag_is_scored_on_level_end() {
+    let mut simulation = Simulation::new(2, false);
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    simulation.place(0, 0, (0, 2), Quat::IDENTITY);
+    simulation.place(0, 0, (2, 2), *DEG_90);
+    simulation.finish_level();
+
+    assert!(simulation.score().points > 0);
+    assert!(simulation.score().points < simulation.score().packed_points);
+}
+
+#[test]
+fn pieces_can_be_placed_with_the_keyboard() {
+    let mut simulation = Simulation::new(1, false);
+
+    simulation.press(KeyCode::Key1);
+    simulation.press(KeyCode::R);
+    simulation.press(KeyCode::Right);
+    simulation.lower_held_piece();
+    simulation.press(KeyCode::Return);
+    simulation.finish_level();
+
+    assert!(simulation.score().points > 0);
+}
+
+#[test]
+fn held_pieces_preview_whether_they_fit() {
+    let mut simulation = Simulation::new(1, false);
+
+    simulation.press(KeyCode::Key1);
+    assert_eq!(simulation.placement_preview(), None);
+
+    simulation.press(KeyCode::R);
+    simulation.press(KeyCode::Right);
+    simulation.lower_held_piece();
+    assert_eq!(simulation.placement_preview(), Some(true));
+
+    // Lifted off the bottom, the piece would float
+    simulation.press(KeyCode::Up);
+    assert_eq!(simulation.placement_preview(), Some(false));
+    simulation.press(KeyCode::Down);
+    simulation.press(KeyCode::Return);
+    simulation.settle();
+    assert_eq!(simulation.placement_preview(), None);
+}
+
+#[test]
+fn pieces_can_only_be_flipped_on_levels_that_allow_it() {
+    let mut simulation = Simulation::new(2, false);
+    simulation.press(KeyCode::Key1);
+    let held = simulation.held_piece().unwrap().1;
+    simulation.press(KeyCode::M);
+    assert_eq!(simulation.held_piece().unwrap().1, held);
+
+    let endless = u16::try_from(LEVELS.len()).unwrap() + 1;
+    let mut simulation = Simulation::new(endless, false);
+    simulation.press(KeyCode::Key1);
+    let held = simulation.held_piece().unwrap().1;
+    simulation.press(KeyCode::M);
+    let flipped = simulation.held_piece().unwrap().1;
+    assert!(!flipped.rotation.abs_diff_eq(held.rotation, 1e-5));
+    simulation.press(KeyCode::M);
+    let unflipped = simulation.held_piece().unwrap().1;
+    assert!(unflipped.rotation.abs_diff_eq(held.rotation, 1e-5));
+}
+
+#[test]
+fn held_pieces_cannot_be_moved_over_belt_pieces() {
+    let mut simulation = Simulation::new(2, false);
+    let belt = simulation.belt_pieces();
+    let other = simulation.piece_position(belt[1]).round();
+
+    simulation.press(KeyCode::Key1);
+    simulation.move_cursor(other);
+
+    let held = simulation.held_piece().unwrap().1;
+    assert_ne!(held.translation.truncate(), other);
+}
+
+#[test]
+fn gamepad_shoulder_buttons_cycle_through_belt_pieces() {
+    let mut simulation = Simulation::new(2, false);
+    let belt = simulation.belt_pieces();
+
+    simulation.press_button(GamepadButtonType::South);
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(belt[0]));
+    simulation.press_button(GamepadButtonType::RightTrigger);
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(belt[1]));
+    simulation.press_button(GamepadButtonType::LeftTrigger);
+    simulation.press_button(GamepadButtonType::LeftTrigger);
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(belt[2]));
+}
+
+#[test]
+fn touching_a_piece_picks_it_up_and_drags_it() {
+    let mut simulation = Simulation::new(2, false);
+    let piece = simulation.belt_pieces()[0];
+    let start = simulation.piece_position(piece);
+
+    simulation.touch(TouchPhase::Started, start);
+    simulation.touch(TouchPhase::Ended, start);
+    let (held, transform) = simulation.held_piece().unwrap();
+    assert_eq!(held, piece);
+
+    // Tapping the held piece rotates it in place
+    let position = transform.translation.truncate();
+    simulation.touch(TouchPhase::Started, position);
+    simulation.touch(TouchPhase::Ended, position);
+    let rotated = simulation.held_piece().unwrap().1;
+    assert_ne!(rotated.rotation, transform.rotation);
+
+    simulation.touch(TouchPhase::Started, position);
+    simulation.touch(TouchPhase::Moved, position + Vec2::Y * 3.);
+    let dragged = simulation.held_piece().unwrap().1;
+    assert!(dragged.translation.y > rotated.translation.y);
+}
+
+#[test]
+fn dragging_a_piece_with_the_mouse_keeps_holding_it_on_release() {
+    let mut simulation = Simulation::new(2, false);
+    let piece = simulation.belt_pieces()[0];
+    let start = simulation.piece_position(piece);
+
+    simulation.move_cursor(start);
+    simulation.mouse(ElementState::Pressed);
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(piece));
+
+    simulation.move_cursor(start + Vec2::Y * 3.);
+    let dragged = simulation.held_piece().unwrap().1;
+    assert!(dragged.translation.y > start.y);
+
+    // On the desktop the piece is placed with another click rather than on
+    // release
+    simulation.mouse(ElementState::Released);
+    simulation.settle();
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(piece));
+}
+
+#[test]
+fn dragging_a_piece_with_a_finger_drops_it_on_release() {
+    let mut simulation = Simulation::new(2, false);
+    let piece = simulation.belt_pieces()[0];
+    let start = simulation.piece_position(piece);
+
+    simulation.touch(TouchPhase::Started, start);
+    simulation.touch(TouchPhase::Moved, start + Vec2::Y * 3.);
+    assert_eq!(simulation.held_piece().map(|(id, _)| id), Some(piece));
+
+    // Let go away from the bags, the piece goes back on the belt
+    simulation.touch(TouchPhase::Ended, start + Vec2::Y * 3.);
+    simulation.settle();
+    assert!(simulation.held_piece().is_none());
+    assert!(simulation.belt_pieces().contains(&piece));
+}
+
+#[test]
+fn restart_button_restarts_the_level() {
+    let mut simulation = Simulation::new(2, false);
+    let belt_pieces = simulation.belt_pieces().len();
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    simulation.press_button(GamepadButtonType::Select);
+    simulation.settle();
+
+    assert!(simulation.level_finished());
+    assert_eq!(simulation.score().points, 0);
+    assert_eq!(simulation.belt_pieces().len(), belt_pieces);
+}
+
+#[test]
+fn undoing_a_placement_returns_the_piece() {
+    let mut simulation = Simulation::new(2, false);
+    let belt_pieces = simulation.belt_pieces();
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    assert!(simulation.score().points > 0);
+
+    simulation.undo();
+    assert_eq!(simulation.score().points, 0);
+    assert_eq!(simulation.score().packed_points, 0);
+    assert_eq!(simulation.belt_pieces(), belt_pieces);
+
+    // Only one placement per bag can be taken back
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    simulation.undo();
+    assert!(simulation.score().points > 0);
+}
+
+#[test]
+fn undoing_on_a_full_belt_deals_the_last_piece_again() {
+    let level = u16::try_from(LEVELS.len()).unwrap() + 1;
+    let mut simulation = Simulation::new(level, false);
+    let belt = simulation.belt_nominos();
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    let next = simulation.belt_nominos();
+    simulation.undo();
+    assert_eq!(simulation.belt_nominos(), belt);
+
+    simulation.place(0, 0, (0, 0), Quat::IDENTITY);
+    assert_eq!(simulation.belt_nominos(), next);
+}
+
+#[test]
+fn undoing_the_last_piece_does_not_stop_the_level_from_ending() {
+    let mut simulation = Simulation::new(1, false);
+
+    simulation.drop_piece(0, 0, (2, 2), Quat::IDENTITY);
+    simulation.undo();
+    simulation.finish_level();
+
+    assert_eq!(simulation.score().points, 8000);
+}
+
+#[test]
+fn robot_placements_cannot_be_undone() {
+    let mut simulation = Simulation::new(11, true);
+
+    simulation.run_until(|s| s.score().points > 0);
+    let points = simulation.score().points;
+    simulation.undo();
+
+    assert_eq!(simulation.score().points, points);
+}
+
+#[test]
+fn robot_places_pieces_on_its_own() {
+    let mut simulation = Simulation::new(11, true);
+    let belt_pieces = simulation.belt_pieces();
+
+    simulation.run_until(|s| s.score().points > 0);
+
+    assert!(!simulation.belt_pieces().starts_with(&belt_pieces[..1]));
+}
+
+#[test]
+fn paused_robot_waits() {
+    let mut simulation = Simulation::new(11, true);
+    let belt_pieces = simulation.belt_pieces();
+
+    simulation.set_state(GameState::Paused);
+    // Long enough for the robot to have placed a piece if it weren't paused
+    simulation.advance(Duration::from_secs(10));
+    assert_eq!(simulation.score().points, 0);
+    assert_eq!(simulation.belt_pieces(), belt_pieces);
+
+    simulation.set_state(GameState::Playing);
+    simulation.run_until(|s| s.score().points > 0);
+}
+
+#[test]
+fn level_select_goes_back_to_the_main_menu() {
+    let mut simulation = Simulation::new(1, false);
+
+    simulation.set_state(GameState::LevelSelect);
+    simulation.press(KeyCode::Escape);
+    assert_eq!(simulation.state(), GameState::MainMenu);
+
+    simulation.set_state(GameState::LevelSelect);
+    simulation.press_button(GamepadButtonType::East);
+    assert_eq!(simulation.state(), GameState::MainMenu);
+}
+
+#[test]
+fn settings_go_back_to_the_menu_that_opened_them() {
+    let mut simulation = Simulation::new(1, false);
+
+    for menu in [GameState::Paused, GameState::MainMenu] {
+        simulation.set_state(menu);
+        simulation.update();
+        simulation.set_state(GameState::Settings);
+        simulation.update();
+
+        simulation.press(KeyCode::Escape);
+        assert_eq!(simulation.state(), menu);
+    }
+}
+
+#[test]
+fn endless_belt_is_reproducible() {
+    let level = u16::try_from(LEVELS.len()).unwrap() + 1;
+
+    let belt = Simulation::new(level, false).belt_nominos();
+
+    assert!(!belt.is_empty());
+    assert_eq!(Simulation::new(level, false).belt_nominos(), belt);
+}
@@ -1,19 +1,8 @@
-use std::{collections::HashSet, iter::repeat, thread};
+use std::{collections::HashSet, iter::repeat, ops::ControlFlow, thread};
 
-use serde::Serialize;
+use rand::{seq::SliceRandom, Rng};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize)]
-pub enum Nomino {
-    TrominoStraight,
-    TrominoL,
-    TetrominoStraight,
-    TetrominoSquare,
-    TetrominoT,
-    TetrominoL,
-    TetrominoLMirrored,
-    TetrominoSkew,
-    TetrominoSkewMirrored,
-}
+use crate::{Nomino, Shape};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 enum RawNomino {
@@ -45,27 +34,29 @@ enum RawNomino {
 }
 
 impl RawNomino {
-    const fn into_nomino(self) -> Nomino {
+    const fn into_shape(self) -> Shape {
         match self {
-            Self::TrominoStraight | Self::TrominoStraight180 => Nomino::TrominoStraight,
+            Self::TrominoStraight | Self::TrominoStraight180 => Shape::new(Nomino::TrominoStraight),
             Self::TrominoL | Self::TrominoL90 | Self::TrominoL180 | Self::TrominoL270 => {
-                Nomino::TrominoL
+                Shape::new(Nomino::TrominoL)
             }
-            Self::TetrominoStraight | Self::TetrominoStraight180 => Nomino::TetrominoStraight,
-            Self::TetrominoSquare => Nomino::TetrominoSquare,
+            Self::TetrominoStraight | Self::TetrominoStraight180 => {
+                Shape::new(Nomino::TetrominoStraight)
+            }
+            Self::TetrominoSquare => Shape::new(Nomino::TetrominoSquare),
             Self::TetrominoT | Self::TetrominoT90 | Self::TetrominoT180 | Self::TetrominoT270 => {
-                Nomino::TetrominoT
+                Shape::new(Nomino::TetrominoT)
             }
             Self::TetrominoL | Self::TetrominoL90 | Self::TetrominoL180 | Self::TetrominoL270 => {
-                Nomino::TetrominoL
+                Shape::new(Nomino::TetrominoL)
             }
             Self::TetrominoLMirrored
             | Self::TetrominoLMirrored90
             | Self::TetrominoLMirrored180
-            | Self::TetrominoLMirrored270 => Nomino::TetrominoLMirrored,
-            Self::TetrominoSkew | Self::TetrominoSkew180 => Nomino::TetrominoSkew,
+            | Self::TetrominoLMirrored270 => Shape::mirrored(Nomino::TetrominoL),
+            Self::TetrominoSkew | Self::TetrominoSkew180 => Shape::new(Nomino::TetrominoSkew),
             Self::TetrominoSkewMirrored | Self::TetrominoSkewMirrored180 => {
-                Nomino::TetrominoSkewMirrored
+                Shape::mirrored(Nomino::TetrominoSkew)
             }
         }
     }
@@ -126,6 +117,7 @@ impl Scratchpad {
         }
     }
 
+    #[allow(clippy::cognitive_complexity)]
     fn extend_search_space(&mut self, depth: u8, block_count: usize) {
         let mut target_row = 0;
         let mut target_col = 0;
@@ -235,7 +227,7 @@ impl Scratchpad {
     }
 }
 
-pub fn generate(width: usize, height: usize) -> HashSet<Vec<Nomino>> {
+pub fn generate(width: usize, height: usize) -> HashSet<Vec<Shape>> {
     let mut bags = HashSet::new();
 
     let seed_search_space = {
@@ -261,8 +253,56 @@ pub fn generate(width: usize, height: usize) -> HashSet<Vec<Nomino>> {
     bags
 }
 
-fn exhaust_scratchpad(mut scratchpad: Scratchpad) -> HashSet<Vec<Nomino>, ahash::RandomState> {
+/// Finds a single way to fill the bag, picking among the possible placements at
+/// random. Enumerating every filling of the larger bags takes minutes, whereas
+/// this only explores until it finds one.
+pub fn generate_one(width: usize, height: usize, rng: &mut impl Rng) -> Option<Vec<Shape>> {
+    let mut scratchpad = Scratchpad::new(width, height);
+    scratchpad.extend_search_space(0, 0);
+    scratchpad.search_space.shuffle(rng);
+
+    let mut filling = None;
+    explore(
+        &mut scratchpad,
+        |pieces| {
+            filling = Some(pieces.to_vec());
+            ControlFlow::Break(())
+        },
+        |new_placements| new_placements.shuffle(rng),
+    );
+    filling
+}
+
+fn exhaust_scratchpad(mut scratchpad: Scratchpad) -> HashSet<Vec<Shape>, ahash::RandomState> {
     let mut bags = HashSet::with_hasher(ahash::RandomState::new());
+    let mut completed_bag = Vec::new();
+
+    explore(
+        &mut scratchpad,
+        |pieces| {
+            completed_bag.extend_from_slice(pieces);
+            completed_bag.sort_unstable();
+
+            if !bags.contains(&completed_bag) {
+                bags.insert(completed_bag.clone());
+            }
+            completed_bag.clear();
+            ControlFlow::Continue(())
+        },
+        |_| {},
+    );
+
+    bags
+}
+
+/// Depth-first search through the scratchpad's search space, calling
+/// `on_filled` with the pieces of every filled bag and `on_extended` with the
+/// placements discovered after each piece is placed.
+fn explore(
+    scratchpad: &mut Scratchpad,
+    mut on_filled: impl FnMut(&[Shape]) -> ControlFlow<()>,
+    mut on_extended: impl FnMut(&mut [(RawNomino, u8, (usize, usize))]),
+) {
     let mut piece_stack = Vec::<(RawNomino, usize, (usize, usize))>::with_capacity(8);
     let mut completed_bag = Vec::new();
 
@@ -284,19 +324,19 @@ fn exhaust_scratchpad(mut scratchpad: Scratchpad) -> HashSet<Vec<Nomino>, ahash:
         piece_stack.push((piece, block_count, (target_row, target_col)));
 
         if block_count == scratchpad.full_count {
-            completed_bag.extend(piece_stack.iter().map(|p| p.0.into_nomino()));
-            completed_bag.sort_unstable();
+            completed_bag.extend(piece_stack.iter().map(|p| p.0.into_shape()));
+            let flow = on_filled(&completed_bag);
+            completed_bag.clear();
 
-            if !bags.contains(&completed_bag) {
-                bags.insert(completed_bag.clone());
+            if flow.is_break() {
+                return;
             }
-            completed_bag.clear();
         } else {
+            let explored = scratchpad.search_space.len();
             scratchpad.extend_search_space(depth + 1, block_count);
+            on_extended(&mut scratchpad.search_space[explored..]);
         }
     }
-
-    bags
 }
 
 #[cfg(test)]
@@ -304,6 +344,7 @@ mod tests {
     use std::io::BufWriter;
 
     use goldenfile::Mint;
+    use rand::{rngs::StdRng, SeedableRng};
     use rstest::rstest;
 
     use super::*;
@@ -320,4 +361,19 @@ mod tests {
         bags.sort_unstable();
         serde_json::to_writer_pretty(writer, &bags).unwrap();
     }
+
+    #[test]
+    fn generated_filling_covers_the_bag() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for (width, height) in [(4, 2), (3, 4), (6, 6)] {
+            let filling = generate_one(width, height, &mut rng).unwrap();
+            let blocks = filling
+                .iter()
+                .map(|shape| shape.nomino.block_count())
+                .sum::<u8>();
+
+            assert_eq!(usize::from(blocks), width * height);
+        }
+    }
 }
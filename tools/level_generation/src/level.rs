@@ -0,0 +1,256 @@
+use std::{fmt::Write, str::FromStr};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{Filling, PieceSequence, SequenceError, Shape};
+
+/// The game's piece colors, in the order they're picked.
+pub const COLORS: [&str; 5] = ["Orange", "Gold", "Blue", "Green", "Pink"];
+
+#[derive(Debug, Clone)]
+pub struct LevelOptions {
+    pub bags: Vec<(u8, u8)>,
+    pub colors: u8,
+    /// Defaults to one filling per bag.
+    pub belt_length: Option<usize>,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+}
+
+/// - Easy: each bag's pieces share a color and are never upside down.
+/// - Normal: pieces may be upside down.
+/// - Hard: every piece has a random color and pieces meant for different bags
+///   are mixed together.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Level {
+    pub bags: Vec<(u8, u8)>,
+    pub belt: Vec<LevelPiece>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LevelPiece {
+    pub shape: Shape,
+    /// Index into [`COLORS`].
+    pub color: u8,
+    pub upside_down: bool,
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Self::Easy),
+            "normal" => Ok(Self::Normal),
+            "hard" => Ok(Self::Hard),
+            _ => Err(format!(
+                "unknown difficulty {s:?}, expected easy, normal, or hard"
+            )),
+        }
+    }
+}
+
+/// Builds a preset belt out of perfect bag fillings, dealt to each bag in turn.
+///
+/// # Errors
+///
+/// If there are no bags or one of them can't be filled.
+///
+/// # Panics
+///
+/// If there are no colors.
+pub fn generate_level(options: &LevelOptions) -> Result<Level, SequenceError> {
+    assert!(
+        (1..=COLORS.len()).contains(&usize::from(options.colors)),
+        "levels need between 1 and {} colors",
+        COLORS.len()
+    );
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut belt = Vec::new();
+    let mut fillings = PieceSequence::new(options.bags.clone(), options.seed)?;
+    for round in 0.. {
+        let done = options
+            .belt_length
+            .map_or(round == options.bags.len(), |length| belt.len() >= length);
+        if done {
+            break;
+        }
+
+        let Filling { pieces, .. } = fillings.next().expect("bags can be filled");
+        let color = rng.gen_range(0..options.colors);
+
+        for shape in pieces {
+            belt.push(LevelPiece {
+                shape,
+                color: if options.difficulty == Difficulty::Hard {
+                    rng.gen_range(0..options.colors)
+                } else {
+                    color
+                },
+                upside_down: options.difficulty != Difficulty::Easy && rng.gen(),
+            });
+        }
+    }
+
+    if options.difficulty == Difficulty::Hard {
+        belt.shuffle(&mut rng);
+    }
+    if let Some(length) = options.belt_length {
+        belt.truncate(length);
+    }
+
+    Ok(Level {
+        bags: options.bags.clone(),
+        belt,
+    })
+}
+
+impl Level {
+    /// Formats the level as a `.level.ron` file.
+    pub fn to_ron(&self) -> String {
+        let mut out = String::from("(\n    belt: Preset([\n");
+        for piece in &self.belt {
+            write!(
+                out,
+                "        (nomino: {:?}, color: {}",
+                piece.shape.nomino,
+                COLORS[usize::from(piece.color)]
+            )
+            .unwrap();
+            if piece.upside_down {
+                out.push_str(", rotation: Deg180");
+            }
+            if piece.shape.mirrored {
+                out.push_str(", mirrored: true");
+            }
+            out.push_str("),\n");
+        }
+        out.push_str("    ]),\n    bags: [\n");
+        for (width, height) in &self.bags {
+            writeln!(out, "        (width: {width}, height: {height}),").unwrap();
+        }
+        out.push_str("    ],\n)\n");
+        out
+    }
+
+    /// Formats the level's belt as a `PresetPiecesConveyorBelt` for levels
+    /// written in Rust.
+    pub fn to_rust(&self) -> String {
+        let mut out = String::from("PresetPiecesConveyorBelt::new([\n");
+        for piece in &self.belt {
+            let rotation = match (piece.shape.mirrored, piece.upside_down) {
+                (false, false) => "Quat::IDENTITY",
+                (false, true) => "*DEG_180",
+                (true, false) => "*DEG_MIRRORED",
+                (true, true) => "*DEG_MIRRORED * *DEG_180",
+            };
+            write!(
+                out,
+                "    Piece {{\n        nomino: Nomino::{:?},\n        color: \
+                 NominoColor::{},\n        rotation: {rotation},\n    }},\n",
+                piece.shape.nomino,
+                COLORS[usize::from(piece.color)]
+            )
+            .unwrap();
+        }
+        out.push_str("])\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(difficulty: Difficulty) -> LevelOptions {
+        LevelOptions {
+            bags: vec![(3, 4), (4, 4)],
+            colors: 3,
+            belt_length: Some(10),
+            difficulty,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn belt_has_the_requested_length() {
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            let level = generate_level(&options(difficulty)).unwrap();
+
+            assert_eq!(level.belt.len(), 10);
+            assert!(level.belt.iter().all(|piece| piece.color < 3));
+        }
+    }
+
+    #[test]
+    fn default_belt_fills_each_bag_once() {
+        let level = generate_level(&LevelOptions {
+            belt_length: None,
+            ..options(Difficulty::Normal)
+        })
+        .unwrap();
+
+        let blocks = level
+            .belt
+            .iter()
+            .map(|piece| usize::from(piece.shape.nomino.block_count()))
+            .sum::<usize>();
+        assert_eq!(blocks, 3 * 4 + 4 * 4);
+    }
+
+    #[test]
+    fn easy_pieces_are_upright_and_share_a_color_per_bag() {
+        let level = generate_level(&LevelOptions {
+            bags: vec![(3, 4)],
+            belt_length: Some(100),
+            ..options(Difficulty::Easy)
+        })
+        .unwrap();
+
+        let mut blocks = 0;
+        let mut color = level.belt[0].color;
+        for piece in level.belt {
+            if blocks == 12 {
+                blocks = 0;
+                color = piece.color;
+            }
+            blocks += piece.shape.nomino.block_count();
+
+            assert_eq!(piece.color, color);
+            assert!(!piece.upside_down);
+        }
+    }
+
+    #[test]
+    fn rust_snippets_spell_out_rotations() {
+        let level = Level {
+            bags: vec![(3, 4)],
+            belt: vec![LevelPiece {
+                shape: Shape::mirrored(crate::Nomino::TetrominoSkew),
+                color: 4,
+                upside_down: true,
+            }],
+        };
+
+        assert_eq!(
+            level.to_rust(),
+            "PresetPiecesConveyorBelt::new([
+    Piece {
+        nomino: Nomino::TetrominoSkew,
+        color: NominoColor::Pink,
+        rotation: *DEG_MIRRORED * *DEG_180,
+    },
+])
+"
+        );
+    }
+}
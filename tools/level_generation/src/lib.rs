@@ -0,0 +1,34 @@
+//! Finds the sets of pieces that perfectly fill Grocery Bagger 9000 bags and
+//! turns them into piece sequences for levels.
+
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::must_use_candidate)]
+#![allow(clippy::missing_panics_doc)]
+
+use rand::Rng;
+
+pub use level::{generate_level, Difficulty, Level, LevelOptions, LevelPiece, COLORS};
+pub use nominos::{Nomino, Shape};
+pub use sequence::{Filling, PieceSequence, SequenceError};
+
+mod bag_fillings;
+mod level;
+mod nominos;
+mod sequence;
+
+/// Every distinct set of pieces that perfectly fills a bag, in sorted order.
+///
+/// Enumerating the larger bags is slow (a 6x6 bag takes minutes), so prefer
+/// [`random_tiling`] when a single tiling will do.
+pub fn tilings(width: u8, height: u8) -> Vec<Vec<Shape>> {
+    let mut tilings = bag_fillings::generate(width.into(), height.into())
+        .into_iter()
+        .collect::<Vec<_>>();
+    tilings.sort_unstable();
+    tilings
+}
+
+/// A random set of pieces that perfectly fills a bag, if there is one.
+pub fn random_tiling(width: u8, height: u8, rng: &mut impl Rng) -> Option<Vec<Shape>> {
+    bag_fillings::generate_one(width.into(), height.into(), rng)
+}
@@ -1,10 +1,116 @@
-use crate::bag_fillings::generate;
+use std::{error::Error, fs, path::PathBuf};
 
-mod bag_fillings;
+use gb9000_level_generation::{generate_level, tilings, Difficulty, LevelOptions, COLORS};
+use pico_args::Arguments;
 
-fn main() {
-    for xy in [(3, 4), (4, 4), (4, 5), (5, 5)] {
-        let bags = generate(xy.0, xy.1);
-        dbg!(xy, bags.len());
+const HELP: &str = "\
+Grocery Bagger 9000 level generator
+
+USAGE:
+  gb9000_level_generation count [WxH...]
+    Prints how many distinct sets of pieces fill each bag size.
+
+  gb9000_level_generation level --bags WxH[,WxH...] [OPTIONS]
+    Generates a level whose belt perfectly fills the bags.
+
+OPTIONS:
+  --colors N          Number of piece colors [default: 1]
+  --length N          Number of pieces on the belt [default: one filling per bag]
+  --difficulty LEVEL  easy, normal, or hard [default: normal]
+  --seed N            Seed for the random choices [default: random]
+  --format FORMAT     ron for a level file or rust for a belt snippet [default: ron]
+  --output PATH       Where to write the level [default: stdout]
+";
+
+enum Format {
+    Ron,
+    Rust,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+        print!("{HELP}");
+        return Ok(());
     }
+
+    match args.subcommand()?.as_deref() {
+        Some("count") => count(args),
+        Some("level") => level(args),
+        Some(command) => Err(format!("unknown command {command:?}\n\n{HELP}").into()),
+        None => Err(HELP.into()),
+    }
+}
+
+fn count(args: Arguments) -> Result<(), Box<dyn Error>> {
+    let mut sizes = args
+        .finish()
+        .iter()
+        .map(|size| parse_size(&size.to_string_lossy()))
+        .collect::<Result<Vec<_>, _>>()?;
+    if sizes.is_empty() {
+        sizes = vec![(3, 4), (4, 4), (4, 5), (5, 5)];
+    }
+
+    for (width, height) in sizes {
+        println!("{width}x{height}: {}", tilings(width, height).len());
+    }
+    Ok(())
+}
+
+fn level(mut args: Arguments) -> Result<(), Box<dyn Error>> {
+    let bags = args.value_from_fn("--bags", |bags| {
+        bags.split(',')
+            .map(parse_size)
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+    let colors = args.opt_value_from_str("--colors")?.unwrap_or(1);
+    if !(1..=COLORS.len()).contains(&usize::from(colors)) {
+        return Err(format!("--colors must be between 1 and {}", COLORS.len()).into());
+    }
+    let options = LevelOptions {
+        bags,
+        colors,
+        belt_length: args.opt_value_from_str("--length")?,
+        difficulty: args
+            .opt_value_from_str::<_, Difficulty>("--difficulty")?
+            .unwrap_or_default(),
+        seed: args
+            .opt_value_from_str("--seed")?
+            .unwrap_or_else(rand::random),
+    };
+    let format = args
+        .opt_value_from_fn("--format", |format| match format {
+            "ron" => Ok(Format::Ron),
+            "rust" => Ok(Format::Rust),
+            _ => Err(format!("unknown format {format:?}, expected ron or rust")),
+        })?
+        .unwrap_or(Format::Ron);
+    let output =
+        args.opt_value_from_os_str("--output", |path| Ok::<_, String>(PathBuf::from(path)))?;
+
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+        return Err(format!("unexpected arguments {remaining:?}").into());
+    }
+
+    let level = generate_level(&options).map_err(|e| e.to_string())?;
+    let level = match format {
+        Format::Ron => level.to_ron(),
+        Format::Rust => level.to_rust(),
+    };
+    eprintln!("Seed: {}", options.seed);
+
+    if let Some(output) = output {
+        fs::write(output, level)?;
+    } else {
+        print!("{level}");
+    }
+    Ok(())
+}
+
+fn parse_size(size: &str) -> Result<(u8, u8), String> {
+    size.split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .ok_or_else(|| format!("invalid bag size {size:?}, expected WxH"))
 }
@@ -0,0 +1,87 @@
+// TODO https://github.com/rust-lang/rust-clippy/issues/6902
+#![allow(clippy::use_self)]
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::prelude::Component;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// The piece shapes, shared with the game. Mirrored pieces share a shape with
+/// their unmirrored counterpart.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+pub enum Nomino {
+    TrominoStraight,
+    TrominoL,
+    TetrominoStraight,
+    TetrominoSquare,
+    TetrominoT,
+    TetrominoL,
+    TetrominoSkew,
+}
+
+/// A shape as it must be laid out in a bag, which is only possible for some
+/// pieces after they've been mirrored.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Shape {
+    pub nomino: Nomino,
+    pub mirrored: bool,
+}
+
+impl Nomino {
+    pub const ALL: [Self; 7] = [
+        Self::TrominoStraight,
+        Self::TrominoL,
+        Self::TetrominoStraight,
+        Self::TetrominoSquare,
+        Self::TetrominoT,
+        Self::TetrominoL,
+        Self::TetrominoSkew,
+    ];
+
+    pub const fn block_count(self) -> u8 {
+        match self {
+            Self::TrominoStraight | Self::TrominoL => 3,
+            Self::TetrominoStraight
+            | Self::TetrominoSquare
+            | Self::TetrominoT
+            | Self::TetrominoL
+            | Self::TetrominoSkew => 4,
+        }
+    }
+}
+
+impl Shape {
+    pub const fn new(nomino: Nomino) -> Self {
+        Self {
+            nomino,
+            mirrored: false,
+        }
+    }
+
+    pub const fn mirrored(nomino: Nomino) -> Self {
+        Self {
+            nomino,
+            mirrored: true,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match (self.nomino, self.mirrored) {
+            (Nomino::TrominoStraight, _) => "TrominoStraight",
+            (Nomino::TrominoL, _) => "TrominoL",
+            (Nomino::TetrominoStraight, _) => "TetrominoStraight",
+            (Nomino::TetrominoSquare, _) => "TetrominoSquare",
+            (Nomino::TetrominoT, _) => "TetrominoT",
+            (Nomino::TetrominoL, false) => "TetrominoL",
+            (Nomino::TetrominoL, true) => "TetrominoLMirrored",
+            (Nomino::TetrominoSkew, false) => "TetrominoSkew",
+            (Nomino::TetrominoSkew, true) => "TetrominoSkewMirrored",
+        }
+    }
+}
+
+impl Serialize for Shape {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
@@ -0,0 +1,129 @@
+use std::{error::Error, fmt};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{random_tiling, Shape};
+
+/// Deals the pieces needed to perfectly fill each bag in turn, cycling through
+/// the bags forever.
+pub struct PieceSequence {
+    bag_sizes: Vec<(u8, u8)>,
+    rng: StdRng,
+    next_bag: usize,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SequenceError {
+    NoBags,
+    Unfillable { width: u8, height: u8 },
+}
+
+/// The shuffled pieces of a random tiling of one bag.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Filling {
+    /// Index of the bag in the sequence's bag sizes.
+    pub bag: usize,
+    pub pieces: Vec<Shape>,
+}
+
+impl PieceSequence {
+    /// # Errors
+    ///
+    /// If there are no bags or one of them can't be filled.
+    pub fn new(bag_sizes: impl Into<Vec<(u8, u8)>>, seed: u64) -> Result<Self, SequenceError> {
+        let bag_sizes = bag_sizes.into();
+        if bag_sizes.is_empty() {
+            return Err(SequenceError::NoBags);
+        }
+        for &(width, height) in &bag_sizes {
+            if random_tiling(width, height, &mut StdRng::seed_from_u64(0)).is_none() {
+                return Err(SequenceError::Unfillable { width, height });
+            }
+        }
+
+        Ok(Self {
+            bag_sizes,
+            rng: StdRng::seed_from_u64(seed),
+            next_bag: 0,
+        })
+    }
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoBags => write!(f, "piece sequences need at least one bag"),
+            Self::Unfillable { width, height } => {
+                write!(f, "{width}x{height} bags cannot be perfectly filled")
+            }
+        }
+    }
+}
+
+impl Error for SequenceError {}
+
+impl Iterator for PieceSequence {
+    type Item = Filling;
+
+    fn next(&mut self) -> Option<Filling> {
+        let bag = self.next_bag;
+        let (width, height) = self.bag_sizes[bag];
+        self.next_bag = (bag + 1) % self.bag_sizes.len();
+
+        let mut pieces = random_tiling(width, height, &mut self.rng)?;
+        pieces.shuffle(&mut self.rng);
+        Some(Filling { bag, pieces })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fillings_cycle_through_the_bags() {
+        let sizes = [(3, 4), (4, 2)];
+        let sequence = PieceSequence::new(sizes, 42).unwrap();
+
+        for (filling, (width, height)) in sequence.zip(sizes.into_iter().cycle()).take(6) {
+            let blocks = filling
+                .pieces
+                .iter()
+                .map(|shape| shape.nomino.block_count())
+                .sum::<u8>();
+
+            assert_eq!(blocks, width * height);
+        }
+    }
+
+    #[test]
+    fn sequences_are_reproducible() {
+        let sizes = [(6, 6), (3, 4)];
+
+        assert_eq!(
+            PieceSequence::new(sizes, 7)
+                .unwrap()
+                .take(4)
+                .collect::<Vec<_>>(),
+            PieceSequence::new(sizes, 7)
+                .unwrap()
+                .take(4)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn unfillable_bags_are_rejected() {
+        assert_eq!(
+            PieceSequence::new([(3, 4), (1, 1)], 42).err(),
+            Some(SequenceError::Unfillable {
+                width: 1,
+                height: 1
+            })
+        );
+        assert_eq!(
+            PieceSequence::new([], 42).err(),
+            Some(SequenceError::NoBags)
+        );
+    }
+}